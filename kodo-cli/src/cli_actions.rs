@@ -1,16 +1,45 @@
 use anyhow::{Context, Result};
+use std::io::{self, BufRead, Write};
 use std::path::Path;
 
-use kodo_core::Activity;
+use chrono::NaiveDate;
+use kodo_core::{Activity, Config, Period, TicketMatcher};
+use kodo_core::analytics::Analytics;
+use kodo_core::compare::{percent_change, Comparison, DateRange};
+use kodo_core::goals::{GoalDirection, GoalProgress};
+use kodo_core::heatmap::{Heatmap, PALETTE};
+use kodo_core::html_report::HtmlReport;
+use kodo_core::invoice::{format_cents, Invoice, InvoiceLedger};
+use kodo_core::output::{
+    compare_output, heatmap_output, period_output, standup_output, stats_output, streak_output,
+    GoalRecord, GoalTotals, GroupRecord, GroupTotals,
+};
+use kodo_core::standup::{previous_working_day, Standup, DEFAULT_TEMPLATE};
+use kodo_core::streaks::StreakStats;
+use kodo_core::timesheet::{format_hours, Timesheet};
+use kodo_core::report::{billable_split, summarize_by_period, time_by_person, time_by_ticket};
+use kodo_core::worklog::{self, WorklogQueue};
 
+use crate::jira::JiraClient;
+use crate::output::{self, OutputFormat};
+
+#[allow(clippy::too_many_arguments)]
 pub fn add_activity(
     activities: &mut Vec<Activity>,
     name: &str,
     minutes: u32,
+    project: Option<String>,
+    billable: Option<bool>,
+    tags: Vec<String>,
+    tickets: &TicketMatcher,
     path: &Path,
 ) -> Result<()> {
     let next_id = activities.iter().map(|a| a.id()).max().unwrap_or(0) + 1;
-    let act = Activity::new_with_id(next_id, name, minutes);
+    let mut act = Activity::new_with_id(next_id, name, minutes);
+    act.tickets = tickets.extract(name);
+    act.project = project;
+    act.billable = billable;
+    act.tags = tags;
     activities.push(act);
     Activity::save_all_to_file(activities, path)
         .with_context(|| format!("Failed to save activities to {:?}", path))?;
@@ -35,20 +64,34 @@ pub fn delete_activity(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn edit_activity(
-    activities: &mut Vec<Activity>,
+    activities: &mut [Activity],
     id: u32,
     new_name: Option<String>,
     new_minutes: Option<u32>,
+    new_project: Option<String>,
+    new_billable: Option<bool>,
+    new_tags: Option<Vec<String>>,
+    tickets: &TicketMatcher,
     path: &Path,
 ) -> Result<()> {
     if let Some(act) = activities.iter_mut().find(|a| a.id() == id) {
         if let Some(name) = new_name {
-            act.name = name;
+            act.rename(&name, tickets);
         }
         if let Some(minutes) = new_minutes {
             act.duration_minutes = minutes;
         }
+        if let Some(project) = new_project {
+            act.project = Some(project);
+        }
+        if let Some(billable) = new_billable {
+            act.billable = Some(billable);
+        }
+        if let Some(tags) = new_tags {
+            act.tags = tags;
+        }
         Activity::save_all_to_file(activities, path)?;
         println!("Activity {} updated successfully!", id);
     } else {
@@ -57,14 +100,22 @@ pub fn edit_activity(
     Ok(())
 }
 
-pub fn list_activities(activities: &[Activity]) {
+fn print_billable_split<'a>(activities: impl IntoIterator<Item = &'a Activity>, config: &Config) {
+    let (billable, internal) = billable_split(activities, config);
+    println!("Billable minutes: {} | Non-billable minutes: {}", billable, internal);
+}
+
+pub fn list_activities(activities: &[Activity], config: &Config, format: OutputFormat) -> Result<()> {
+    if format != OutputFormat::Table {
+        return output::print_activities(format, activities, config);
+    }
     if activities.is_empty() {
         println!("No activities recorded yet.");
-        return;
+        return Ok(());
     }
 
     let mut sorted = activities.to_vec();
-    sorted.sort_by(|a, b| b.duration_minutes().cmp(&a.duration_minutes()));
+    sorted.sort_by_key(|a| std::cmp::Reverse(a.duration_minutes()));
     let total: u32 = activities.iter().map(|a| a.duration_minutes()).sum();
 
     println!("ID | Name     | Duration (mins)");
@@ -74,23 +125,30 @@ pub fn list_activities(activities: &[Activity]) {
     }
     println!("-------------------------------");
     println!("Total minutes: {}", total);
+    print_billable_split(activities, config);
+    Ok(())
 }
 
 pub fn filter_activities(
     activities: &[Activity],
     min: Option<u32>,
     max: Option<u32>,
-) {
+    config: &Config,
+    format: OutputFormat,
+) -> Result<()> {
     let filtered: Vec<&Activity> = activities.iter()
         .filter(|a| {
-            (min.map_or(true, |min_val| a.duration_minutes() >= min_val)) &&
-            (max.map_or(true, |max_val| a.duration_minutes() <= max_val))
+            (min.is_none_or(|min_val| a.duration_minutes() >= min_val)) &&
+            (max.is_none_or(|max_val| a.duration_minutes() <= max_val))
         })
         .collect();
 
+    if format != OutputFormat::Table {
+        return output::print_activities(format, filtered.iter().copied(), config);
+    }
     if filtered.is_empty() {
         println!("No activities match the filter criteria.");
-        return;
+        return Ok(());
     }
 
     println!("Filtered activities:");
@@ -104,4 +162,634 @@ pub fn filter_activities(
     let average: f32 = total as f32 / filtered.len() as f32;
     println!("\nTotal minutes: {}", total);
     println!("Average minutes: {:.2}", average);
-}
\ No newline at end of file
+    print_billable_split(filtered.iter().copied(), config);
+    Ok(())
+}
+
+/// Prints time per ticket or person as machine-readable output.
+fn print_groups<'a>(
+    format: OutputFormat,
+    group: &'static str,
+    totals: Vec<(String, u32)>,
+    grouped: impl IntoIterator<Item = &'a Activity> + Clone,
+    activities: &[Activity],
+    config: &Config,
+) -> Result<()> {
+    let minutes: u32 = grouped.clone().into_iter().map(|a| a.duration_minutes).sum();
+    let (billable_minutes, non_billable_minutes) = billable_split(grouped, config);
+    let items: Vec<GroupRecord> = totals
+        .into_iter()
+        .map(|(key, minutes)| GroupRecord { group, key, minutes })
+        .collect();
+    let totals = GroupTotals {
+        minutes,
+        ungrouped_minutes: activities.iter().map(|a| a.duration_minutes).sum::<u32>() - minutes,
+        billable_minutes,
+        non_billable_minutes,
+    };
+    output::print(format, &items, &totals)
+}
+
+pub fn report_by_ticket(activities: &[Activity], config: &Config, format: OutputFormat) -> Result<()> {
+    let totals = time_by_ticket(activities);
+    if format != OutputFormat::Table {
+        let ticketed = activities.iter().filter(|a| !a.tickets().is_empty());
+        return print_groups(format, "ticket", totals, ticketed, activities, config);
+    }
+    if totals.is_empty() {
+        println!("No activities are linked to a ticket.");
+        return Ok(());
+    }
+
+    let untracked: u32 = activities.iter()
+        .filter(|a| a.tickets().is_empty())
+        .map(|a| a.duration_minutes())
+        .sum();
+
+    println!("Ticket       | Duration (mins)");
+    println!("-------------------------------");
+    for (ticket, minutes) in &totals {
+        println!("{:12} | {:>5}", ticket, minutes);
+    }
+    println!("-------------------------------");
+    println!("Without ticket: {} min", untracked);
+    print_billable_split(activities.iter().filter(|a| !a.tickets().is_empty()), config);
+    Ok(())
+}
+
+pub fn report_by_person(activities: &[Activity], config: &Config, format: OutputFormat) -> Result<()> {
+    let totals = time_by_person(activities);
+    if format != OutputFormat::Table {
+        let shared = activities.iter().filter(|a| !a.participants().is_empty());
+        return print_groups(format, "person", totals, shared, activities, config);
+    }
+    if totals.is_empty() {
+        println!("No activities have recorded participants.");
+        return Ok(());
+    }
+
+    println!("Person                         | Duration (mins)");
+    println!("-----------------------------------------------");
+    for (person, minutes) in &totals {
+        println!("{:30} | {:>5}", person, minutes);
+    }
+    println!("-----------------------------------------------");
+    print_billable_split(activities.iter().filter(|a| !a.participants().is_empty()), config);
+    Ok(())
+}
+
+/// Walks through proposed activities one by one and saves the accepted ones.
+pub fn review_drafts(
+    activities: &mut Vec<Activity>,
+    drafts: Vec<Activity>,
+    tickets: &TicketMatcher,
+    path: &Path,
+) -> Result<()> {
+    if drafts.is_empty() {
+        println!("No uncommitted editing sessions found.");
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut accepted = 0;
+
+    println!("Found {} editing session(s) since the last commit.", drafts.len());
+    'drafts: for (i, mut draft) in drafts.into_iter().enumerate() {
+        println!();
+        println!("[{}] {} | {} min | {}", i + 1, draft.date, draft.duration_minutes(), draft.name());
+        loop {
+            print!("Add this activity? [y]es / [n]o / [e]dit name / [q]uit: ");
+            io::stdout().flush()?;
+            let Some(answer) = lines.next().transpose()? else { break 'drafts };
+            match answer.trim() {
+                "y" | "Y" => {
+                    draft.id = activities.iter().map(|a| a.id()).max().unwrap_or(0) + 1;
+                    activities.push(draft);
+                    accepted += 1;
+                    break;
+                }
+                "n" | "N" => break,
+                "e" | "E" => {
+                    print!("New name: ");
+                    io::stdout().flush()?;
+                    if let Some(name) = lines.next().transpose()?
+                        && !name.trim().is_empty()
+                    {
+                        draft.name = name.trim().to_string();
+                        draft.tickets = tickets.extract(&draft.name);
+                    }
+                    println!("[{}] {} | {} min | {}", i + 1, draft.date, draft.duration_minutes(), draft.name());
+                }
+                "q" | "Q" => break 'drafts,
+                _ => {}
+            }
+        }
+    }
+
+    if accepted > 0 {
+        Activity::save_all_to_file(activities, path)
+            .with_context(|| format!("Failed to save activities to {:?}", path))?;
+    }
+    println!("{} activity(ies) added.", accepted);
+    Ok(())
+}
+
+pub fn report_by_period(activities: &[Activity], period: Period, config: &Config, format: OutputFormat) -> Result<()> {
+    let calendar = config.calendar()?;
+    if format != OutputFormat::Table {
+        let (items, totals) = period_output(activities, period, config, &calendar);
+        return output::print(format, &items, &totals);
+    }
+    let summaries = summarize_by_period(activities, period, &calendar);
+    if summaries.is_empty() {
+        println!("No activities recorded yet.");
+        return Ok(());
+    }
+
+    for summary in &summaries {
+        let heading = match period {
+            Period::Day => summary.start.format("%Y-%m-%d (%a)").to_string(),
+            Period::Week => format!("Week of {}", summary.start.format("%Y-%m-%d (%a)")),
+            Period::Month => summary.start.format("%B %Y").to_string(),
+        };
+        println!("{}", heading);
+        println!("-------------------------------");
+        println!("Total minutes: {} across {} activities", summary.total_minutes, summary.activity_count);
+        println!("Average minutes: {:.2} per activity", summary.average_per_activity());
+        if period != Period::Day {
+            println!("Average minutes: {:.2} per active day ({} days)", summary.average_per_active_day(), summary.active_days);
+        }
+        let end = calendar.period_end(summary.start, period);
+        print_billable_split(
+            activities.iter().filter(|a| calendar.day_of(a).is_some_and(|d| d >= summary.start && d < end)),
+            config,
+        );
+        println!("Top activities:");
+        for (name, minutes) in summary.top_activities.iter().take(3) {
+            println!("  {:>5} min  {}", minutes, name);
+        }
+        println!();
+    }
+
+    let total: u32 = summaries.iter().map(|s| s.total_minutes).sum();
+    println!("Total minutes: {}", total);
+    print_billable_split(activities, config);
+    Ok(())
+}
+
+pub fn write_html_report(activities: &[Activity], config: &Config, range: &str, out: &str) -> Result<()> {
+    let calendar = config.calendar()?;
+    let range = DateRange::parse(range, &calendar, calendar.today())?;
+    let report = HtmlReport::build(activities, range, config)?;
+    std::fs::write(out, report.to_html()).with_context(|| format!("Failed to write {}", out))?;
+    eprintln!(
+        "{}: {} across {} activities, written to {}",
+        report.title(),
+        format_hours(report.total_minutes()),
+        report.activities.len(),
+        out,
+    );
+    Ok(())
+}
+
+pub fn write_timesheet(
+    activities: &[Activity],
+    from: NaiveDate,
+    to: NaiveDate,
+    project: Option<&str>,
+    config: &Config,
+    out: &str,
+    html: bool,
+) -> Result<()> {
+    let sheet = Timesheet::build(activities, from, to, project, config)?;
+    if sheet.lines.is_empty() {
+        println!("No activities between {} and {}.", from, to);
+        return Ok(());
+    }
+
+    let csv_path = format!("{}.csv", out);
+    std::fs::write(&csv_path, sheet.to_csv()?)
+        .with_context(|| format!("Failed to write {}", csv_path))?;
+
+    let (printable_path, printable) = if html {
+        (format!("{}.html", out), sheet.to_html())
+    } else {
+        (format!("{}.md", out), sheet.to_markdown())
+    };
+    std::fs::write(&printable_path, printable)
+        .with_context(|| format!("Failed to write {}", printable_path))?;
+
+    for (day, minutes) in sheet.day_totals() {
+        println!("{} | {:>6}", day, format_hours(minutes));
+    }
+    println!("-------------------------------");
+    println!("Total: {} ({} lines)", format_hours(sheet.total_minutes()), sheet.lines.len());
+    println!("Billable: {} | Non-billable: {}",
+        format_hours(sheet.billable_minutes()),
+        format_hours(sheet.total_minutes() - sheet.billable_minutes()));
+    println!("Timesheet written to {} and {}", csv_path, printable_path);
+    Ok(())
+}
+
+pub struct InvoiceOptions<'a> {
+    pub out: Option<&'a str>,
+    pub html: bool,
+    pub dry_run: bool,
+}
+
+pub fn create_invoice(
+    activities: &mut [Activity],
+    path: &Path,
+    ledger_path: &Path,
+    project: &str,
+    month: NaiveDate,
+    config: &Config,
+    options: &InvoiceOptions,
+) -> Result<()> {
+    let calendar = config.calendar()?;
+    let to = calendar.period_end(month, Period::Month).pred_opt().unwrap_or(month);
+    let mut ledger = InvoiceLedger::load_from_file(ledger_path)?;
+    let number = ledger.next_number();
+    let invoice = Invoice::build(&number, activities, project, month, to, config, &calendar)?;
+
+    if invoice.items.is_empty() {
+        println!("No unbilled time for {} between {} and {}.", project, month, to);
+        return Ok(());
+    }
+
+    let stem = options.out.unwrap_or(&number);
+    let (out_path, document) = if options.html {
+        (format!("{}.html", stem), invoice.to_html())
+    } else {
+        (format!("{}.md", stem), invoice.to_markdown())
+    };
+    let summary = format!("Invoice {} for {}: {} items, {}, {} {}",
+        number, project, invoice.items.len(), format_hours(invoice.total_minutes()),
+        format_cents(invoice.total_cents()), invoice.currency);
+
+    if options.dry_run {
+        println!("{}", document.trim_end());
+        println!("\n{}", summary);
+        println!("Dry run: nothing written or recorded.");
+        return Ok(());
+    }
+
+    std::fs::write(&out_path, document)
+        .with_context(|| format!("Failed to write {}", out_path))?;
+    println!("{}", summary);
+
+    ledger.issue(&invoice, activities);
+    Activity::save_all_to_file(activities, path)
+        .with_context(|| format!("Failed to save activities to {:?}", path))?;
+    ledger.save_to_file(ledger_path)
+        .with_context(|| format!("Failed to save invoice ledger to {:?}", ledger_path))?;
+    println!("Written to {} and recorded in {:?}", out_path, ledger_path);
+    Ok(())
+}
+
+pub fn export_worklogs(
+    activities: &[Activity],
+    queue_path: &Path,
+    from: NaiveDate,
+    to: NaiveDate,
+    project: Option<&str>,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    let calendar = config.calendar()?;
+    let mut queue = WorklogQueue::load_from_file(queue_path)?;
+    let uploads: Vec<_> = worklog::worklogs(activities, from, to, project, &calendar)
+        .into_iter()
+        .filter(|u| !queue.contains(u.activity_id, &u.issue))
+        .collect();
+
+    if uploads.is_empty() {
+        println!("No new Jira worklogs between {} and {}.", from, to);
+        return Ok(());
+    }
+    for upload in &uploads {
+        println!("{:<12} {}  {:>6}  {}",
+            upload.issue,
+            upload.worklog.started.get(..16).unwrap_or(&upload.worklog.started).replace('T', " "),
+            format_hours(upload.worklog.time_spent_seconds / 60),
+            upload.worklog.comment.lines().next().unwrap_or(""));
+    }
+    let minutes: u32 = uploads.iter().map(|u| u.worklog.time_spent_seconds / 60).sum();
+    if dry_run {
+        println!("Dry run: {} worklogs, {}, not queued.", uploads.len(), format_hours(minutes));
+        return Ok(());
+    }
+
+    let added = queue.add(uploads);
+    queue.save_to_file(queue_path)
+        .with_context(|| format!("Failed to save worklog queue to {:?}", queue_path))?;
+    println!("Queued {} worklogs ({}) in {:?}; {} pending in total. Send them with `kodo jira submit`.",
+        added, format_hours(minutes), queue_path, queue.pending.len());
+    Ok(())
+}
+
+pub fn submit_worklogs(queue_path: &Path, config: &Config) -> Result<()> {
+    let mut queue = WorklogQueue::load_from_file(queue_path)?;
+    if queue.pending.is_empty() {
+        println!("No worklogs waiting in {:?}. Queue some with `kodo jira export`.", queue_path);
+        return Ok(());
+    }
+    let Some(url) = config.jira.url.as_deref() else {
+        anyhow::bail!("Set jira.url in the config to the address of your Jira site");
+    };
+    let token = std::env::var(worklog::TOKEN_VAR)
+        .with_context(|| format!("Set {} to your Jira API token", worklog::TOKEN_VAR))?;
+
+    let client = JiraClient::new(url, config.jira.email.as_deref(), &token);
+    let save = |queue: &WorklogQueue| {
+        queue.save_to_file(queue_path)
+            .with_context(|| format!("Failed to save worklog queue to {:?}", queue_path))
+    };
+    // Saved after every accepted worklog, so an interrupted run doesn't send
+    // it again
+    let summary = queue.submit(&client, save)?;
+    save(&queue)?;
+
+    println!("Sent {} worklogs to {}.", summary.uploaded, url);
+    for (issue, error) in &summary.failed {
+        println!("  {}: {}", issue, error);
+    }
+    if !summary.failed.is_empty() {
+        println!("{} worklogs failed and stay queued; run `kodo jira submit` again to retry.", summary.failed.len());
+    }
+    Ok(())
+}
+
+pub fn show_streaks(activities: &[Activity], config: &Config, format: OutputFormat) -> Result<()> {
+    let calendar = config.calendar()?;
+    let stats = StreakStats::compute(activities, &calendar, calendar.today());
+    if format != OutputFormat::Table {
+        let (weeks, totals) = streak_output(&stats);
+        return output::print(format, &weeks, &totals);
+    }
+    if stats.active_days == 0 {
+        println!("No activities recorded yet.");
+        return Ok(());
+    }
+
+    println!("Current streak: {} days", stats.current);
+    match stats.longest_end {
+        Some(end) => println!("Longest streak: {} days (ended {})", stats.longest, end),
+        None => println!("Longest streak: {} days", stats.longest),
+    }
+    println!("Active days: {} | Missed days: {}", stats.active_days, stats.missed_days.len());
+    if !stats.missed_days.is_empty() {
+        let recent: Vec<String> = stats.missed_days.iter().rev().take(5).map(|d| d.to_string()).collect();
+        println!("Recently missed: {}", recent.join(", "));
+    }
+
+    println!();
+    println!("Week of     | Active days");
+    println!("-------------------------------");
+    for (week, active) in stats.weekly.iter().rev().take(8) {
+        println!("{} | {}{} {}/7", week, "#".repeat(*active as usize), ".".repeat(7 - *active as usize), active);
+    }
+    Ok(())
+}
+
+pub fn print_heatmap(activities: &[Activity], config: &Config, weeks: usize, color: bool, format: OutputFormat) -> Result<()> {
+    let calendar = config.calendar()?;
+    let heatmap = Heatmap::build(activities, &calendar, calendar.today(), weeks);
+    if format != OutputFormat::Table {
+        let (days, totals) = heatmap_output(&heatmap);
+        return output::print(format, &days, &totals);
+    }
+    let cell = |level: usize| {
+        if color {
+            format!("\x1b[38;5;{}m■\x1b[0m ", PALETTE[level])
+        } else {
+            format!("{} ", [".", "░", "▒", "▓", "█"][level])
+        }
+    };
+
+    // Month names above the first week that starts in each month
+    let mut header = String::from("    ");
+    let mut last_month = None;
+    for week in 0..heatmap.weeks {
+        let Some((day, _)) = heatmap.cell(week, 0) else { break };
+        let col = 4 + week * 2;
+        if last_month != Some(day.format("%b").to_string()) && header.chars().count() <= col {
+            header.push_str(&" ".repeat(col - header.chars().count()));
+            header.push_str(&day.format("%b").to_string());
+            last_month = Some(day.format("%b").to_string());
+        }
+    }
+    println!("{}", header);
+
+    for row in 0..7 {
+        let label = if row % 2 == 0 {
+            (heatmap.start + chrono::Days::new(row as u64)).format("%a").to_string()
+        } else {
+            String::new()
+        };
+        let mut line = format!("{:4}", label);
+        for week in 0..heatmap.weeks {
+            if let Some((_, minutes)) = heatmap.cell(week, row) {
+                line.push_str(&cell(heatmap.level(minutes)));
+            }
+        }
+        println!("{}", line.trim_end());
+    }
+
+    let legend: String = (0..5).map(cell).collect();
+    println!();
+    println!("    Less {}More", legend);
+    println!("    {} min over {} weeks, busiest day {} min", heatmap.total_minutes(), heatmap.weeks, heatmap.max_minutes());
+    Ok(())
+}
+
+pub fn show_goals(activities: &[Activity], config: &Config, format: OutputFormat) -> Result<()> {
+    if format != OutputFormat::Table {
+        let calendar = config.calendar()?;
+        let now = calendar.now();
+        let items: Vec<GoalRecord> = config.goals
+            .iter()
+            .map(|goal| GoalRecord::new(&GoalProgress::compute(goal, activities, &calendar, now)))
+            .collect();
+        return output::print(format, &items, &GoalTotals::new(&items));
+    }
+    if config.goals.is_empty() {
+        println!("No goals configured. Add some under \"goals\" in the config file.");
+        return Ok(());
+    }
+
+    let calendar = config.calendar()?;
+    let now = calendar.now();
+    for goal in &config.goals {
+        let progress = GoalProgress::compute(goal, activities, &calendar, now);
+        let direction = match goal.direction {
+            GoalDirection::Min => "at least",
+            GoalDirection::Max => "at most",
+        };
+        let filled = ((progress.ratio() * 20.0).round() as usize).min(20);
+        println!("{} ({}, {} {} per {})", goal.name, goal.scope(), direction, format_hours(goal.minutes), goal.window_name());
+        println!(
+            "  [{}{}] {} / {} ({:.0}%) | {:.0}% of the {} gone | {}",
+            "#".repeat(filled),
+            "-".repeat(20 - filled),
+            format_hours(progress.minutes),
+            format_hours(goal.minutes),
+            progress.ratio() * 100.0,
+            progress.elapsed * 100.0,
+            goal.window_name(),
+            progress.status_label(),
+        );
+    }
+    Ok(())
+}
+
+pub fn print_standup(
+    activities: &[Activity],
+    config: &Config,
+    date: Option<NaiveDate>,
+    template_path: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let calendar = config.calendar()?;
+    let day = date.unwrap_or_else(|| previous_working_day(calendar.today()));
+    if format != OutputFormat::Table {
+        let (items, totals) = standup_output(&Standup::build(activities, day, &calendar));
+        return output::print(format, &items, &totals);
+    }
+    let template = match template_path {
+        Some(p) => std::fs::read_to_string(p).with_context(|| format!("Failed to read template {:?}", p))?,
+        None => config.standup_template.clone().unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+    };
+
+    let standup = Standup::build(activities, day, &calendar);
+    println!("{}", standup.render(&template).trim_end());
+    Ok(())
+}
+
+fn print_change_row(label: &str, previous: u32, current: u32, as_hours: bool) {
+    let fmt = |v: u32| if as_hours { format_hours(v) } else { v.to_string() };
+    let diff = current as i64 - previous as i64;
+    let sign = match diff {
+        d if d < 0 => "-",
+        0 => "",
+        _ => "+",
+    };
+    let diff = if as_hours { format_hours(diff.unsigned_abs() as u32) } else { diff.abs().to_string() };
+    let percent = match percent_change(previous, current) {
+        Some(p) => format!("{:+.0}%", p),
+        None if current > 0 => "new".to_string(),
+        None => "-".to_string(),
+    };
+    println!("{:<20} | {:>10} | {:>10} | {}{} ({})", label, fmt(previous), fmt(current), sign, diff, percent);
+}
+
+pub fn compare_periods(
+    activities: &[Activity],
+    config: &Config,
+    this: &str,
+    vs: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let calendar = config.calendar()?;
+    let today = calendar.today();
+    let current = DateRange::parse(this, &calendar, today)?;
+    let previous = match vs {
+        Some(spec) => DateRange::parse(spec, &calendar, today)?,
+        None => current.previous(),
+    };
+    let comparison = Comparison::build(activities, current, previous, &calendar);
+    if format != OutputFormat::Table {
+        let (rows, totals) = compare_output(&comparison);
+        return output::print(format, &rows, &totals);
+    }
+    let (before, after) = (&comparison.previous, &comparison.current);
+
+    println!("Comparing {} to {} with {} to {}", current.from, current.to, previous.from, previous.to);
+    println!();
+    println!("{:<20} | {:>10} | {:>10} | Change", "", "Before", "Now");
+    println!("-------------------------------------------------------------");
+    print_change_row("Total time", before.total_minutes, after.total_minutes, true);
+    print_change_row("Activities", before.activity_count as u32, after.activity_count as u32, false);
+    print_change_row("Commits", before.commit_count as u32, after.commit_count as u32, false);
+
+    for (title, rows) in [("Per project", comparison.projects()), ("Per tag", comparison.tags())] {
+        if rows.is_empty() {
+            continue;
+        }
+        println!();
+        println!("{}", title);
+        println!("-------------------------------------------------------------");
+        for (key, before, after) in rows {
+            print_change_row(&key, before, after, true);
+        }
+    }
+    Ok(())
+}
+
+fn bar(value: u32, max: u32, width: usize) -> String {
+    "#".repeat((value as usize * width).div_ceil(max.max(1) as usize))
+}
+
+pub fn show_stats(activities: &[Activity], config: &Config, format: OutputFormat) -> Result<()> {
+    let calendar = config.calendar()?;
+    if format != OutputFormat::Table {
+        let (tags, totals) = stats_output(&Analytics::compute(activities, &calendar));
+        return output::print(format, &tags, &totals);
+    }
+    if activities.is_empty() {
+        println!("No activities recorded yet.");
+        return Ok(());
+    }
+    let stats = Analytics::compute(activities, &calendar);
+    let d = &stats.durations;
+    let describe = |id: Option<u32>| {
+        activities.iter()
+            .find(|a| Some(a.id) == id)
+            .map(|a| format!("{} ({} min, #{})", a.name.lines().next().unwrap_or(""), a.duration_minutes, a.id))
+            .unwrap_or_default()
+    };
+
+    println!("Activities: {} | Total: {} min", d.count, d.total);
+    println!("Mean: {:.1} min | Median: {:.1} min | Std dev: {:.1} min", d.mean, d.median, d.std_dev);
+    println!("Percentiles: p75 {:.1} | p90 {:.1} | p95 {:.1} min", d.p75, d.p90, d.p95);
+    println!("Longest: {}", describe(stats.longest));
+    println!("Shortest: {}", describe(stats.shortest));
+
+    println!();
+    println!("Duration   | Activities");
+    println!("-------------------------------");
+    let most = stats.buckets.iter().map(|(_, n)| *n as u32).max().unwrap_or(0);
+    for (label, count) in &stats.buckets {
+        println!("{:10} | {:>3} {}", label, count, bar(*count as u32, most, 30));
+    }
+
+    println!();
+    println!("Weekday    | Minutes");
+    println!("-------------------------------");
+    let most = stats.by_weekday.iter().map(|(_, m)| *m).max().unwrap_or(0);
+    for (weekday, minutes) in &stats.by_weekday {
+        println!("{:10} | {:>5} {}", weekday.to_string(), minutes, bar(*minutes, most, 30));
+    }
+
+    if stats.has_times() {
+        println!();
+        println!("Hour       | Minutes");
+        println!("-------------------------------");
+        let most = stats.by_hour.iter().copied().max().unwrap_or(0);
+        for (hour, minutes) in stats.by_hour.iter().enumerate().filter(|(_, m)| **m > 0) {
+            println!("{:02}:00      | {:>5} {}", hour, minutes, bar(*minutes, most, 30));
+        }
+    }
+
+    if !stats.by_tag.is_empty() {
+        println!();
+        println!("Tag        | Count | Total | Mean | Median");
+        println!("-------------------------------------------");
+        for tag in &stats.by_tag {
+            let t = &tag.durations;
+            println!("{:10} | {:>5} | {:>5} | {:>4.0} | {:>6.0}", tag.tag, t.count, t.total, t.mean, t.median);
+        }
+    }
+    Ok(())
+}
@@ -2,37 +2,196 @@ use anyhow::{Context, Result};
 use git2::Repository;
 use std::path::Path;
 use chrono::{DateTime, Utc, Local};
-use kodo_core::Activity;
+use kodo_core::{Activity, Source, TicketMatcher};
+use kodo_core::activity::add_participant;
 
 pub fn open_repo(path: &Path) -> Result<Repository> {
     Repository::open(path)
         .with_context(|| format!("Failed to open git repository at {:?}", path))
 }
 
-pub fn get_github_activities(repo_path: &Path, max: usize) -> Result<Vec<Activity>> {
+/// How merge commits are turned into activities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MergeMode {
+    /// Import merge commits like any other commit
+    #[default]
+    Include,
+    /// Leave merge commits out
+    Skip,
+    /// Replace each merged branch with one activity spanning its commits
+    Collapse,
+    /// Follow only the first parent of each merge
+    FirstParent,
+}
+
+struct CommitInfo {
+    oid: git2::Oid,
+    sha: String,
+    author: String,
+    message: String,
+    time: DateTime<Utc>,
+    participants: Vec<String>,
+    tickets: Vec<String>,
+    patch_id: Option<String>,
+    /// Set for collapsed branches; other commits last until the next commit.
+    span_minutes: Option<u32>,
+}
+
+/// Commits are filed under the name of the repository's directory.
+pub fn repo_project(repo: &Repository) -> Option<String> {
+    let dir = repo.workdir().unwrap_or(repo.path());
+    dir.file_name().map(|n| n.to_string_lossy().trim_end_matches(".git").to_string())
+}
+
+fn signature_name(sig: &git2::Signature) -> String {
+    match (sig.name(), sig.email()) {
+        (Some(name), Some(email)) => format!("{} <{}>", name, email),
+        (Some(name), None) => name.to_string(),
+        (None, Some(email)) => format!("<{}>", email),
+        (None, None) => "unknown".to_string(),
+    }
+}
+
+/// The commit author followed by everyone named in `Co-authored-by:` trailers,
+/// each person once.
+fn participants(commit: &git2::Commit, message: &str) -> Vec<String> {
+    let mut people = vec![signature_name(&commit.author())];
+    if let Ok(trailers) = git2::message_trailers_strs(message) {
+        for (key, value) in trailers.iter() {
+            if key.eq_ignore_ascii_case("co-authored-by") {
+                add_participant(&mut people, value.trim().to_string());
+            }
+        }
+    }
+    people
+}
+
+/// Stable id of the change a commit introduces, shared by rebased or
+/// cherry-picked copies. Merge commits have none, and neither do commits
+/// without changed lines (such as `--allow-empty` markers), which would
+/// otherwise all share one id.
+fn patch_id(repo: &Repository, commit: &git2::Commit) -> Option<String> {
+    if commit.parent_count() > 1 {
+        return None;
+    }
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree().ok()?),
+        Err(_) => None,
+    };
+    let tree = commit.tree().ok()?;
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None).ok()?;
+    let stats = diff.stats().ok()?;
+    if stats.insertions() + stats.deletions() == 0 {
+        return None;
+    }
+    diff.patchid(None).ok().map(|oid| oid.to_string())
+}
+
+fn commit_info(repo: &Repository, commit: &git2::Commit, tickets: &TicketMatcher) -> Result<CommitInfo> {
+    let message = commit.message().unwrap_or("no message").to_string();
+    let timestamp = commit.time().seconds();
+    let time: DateTime<Utc> = DateTime::from_timestamp(timestamp, 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?;
+
+    Ok(CommitInfo {
+        oid: commit.id(),
+        sha: commit.id().to_string(),
+        author: signature_name(&commit.author()),
+        participants: participants(commit, &message),
+        tickets: tickets.extract(&message),
+        patch_id: patch_id(repo, commit),
+        span_minutes: None,
+        message,
+        time,
+    })
+}
+
+/// Whether one commit is the other or an ancestor of it, i.e. both lie on
+/// one line of history. Commits that can't be found count as unrelated.
+fn on_one_line(repo: &Repository, a: git2::Oid, b: git2::Oid) -> bool {
+    a == b
+        || repo.graph_descendant_of(a, b).unwrap_or(false)
+        || repo.graph_descendant_of(b, a).unwrap_or(false)
+}
+
+/// Folds the commits a merge brought in into the merge itself, so the
+/// branches become a single activity lasting from their first commit to the
+/// merge. Octopus merges fold in every merged branch.
+fn collapse_branch(repo: &Repository, merge: &git2::Commit, tickets: &TicketMatcher) -> Result<CommitInfo> {
+    let mut info = commit_info(repo, merge, tickets)?;
+    let mut walk = repo.revwalk().context("Failed to create revwalk")?;
+    for parent in merge.parent_ids().skip(1) {
+        walk.push(parent)?;
+    }
+    walk.hide(merge.parent_id(0)?)?;
+
+    let mut earliest = info.time;
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let branch_commit = commit_info(repo, &commit, tickets)?;
+        earliest = earliest.min(branch_commit.time);
+        for person in branch_commit.participants {
+            add_participant(&mut info.participants, person);
+        }
+        for ticket in branch_commit.tickets {
+            if !info.tickets.contains(&ticket) {
+                info.tickets.push(ticket);
+            }
+        }
+    }
+
+    info.span_minutes = Some(std::cmp::max((info.time - earliest).num_minutes() as u32, 1));
+    Ok(info)
+}
+
+pub fn get_github_activities(
+    repo_path: &Path,
+    max: usize,
+    tickets: &TicketMatcher,
+    merges: MergeMode,
+) -> Result<Vec<Activity>> {
     let repo = open_repo(repo_path)?;
+    let repo_dir = repo.workdir().unwrap_or(repo.path()).display().to_string();
+    let project = repo_project(&repo);
     let mut revwalk = repo.revwalk().context("Failed to create revwalk")?;
     revwalk.push_head().context("Failed to push HEAD")?;
+    if matches!(merges, MergeMode::FirstParent | MergeMode::Collapse) {
+        revwalk.simplify_first_parent()?;
+    }
 
-    let mut commits = Vec::new();
-    for oid_result in revwalk.take(max) {
-        let oid = oid_result?;
-        let commit = repo.find_commit(oid)?;
-        let msg = commit.message().unwrap_or("no message").to_string();
-        let timestamp = commit.time().seconds();
-        let naive = chrono::NaiveDateTime::from_timestamp_opt(timestamp, 0)
-            .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?;
-        let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
-
-        commits.push((msg, datetime));
+    let mut commits: Vec<CommitInfo> = Vec::new();
+    for oid_result in revwalk {
+        if commits.len() >= max {
+            break;
+        }
+        let commit = repo.find_commit(oid_result?)?;
+        let is_merge = commit.parent_count() > 1;
+        let info = match merges {
+            MergeMode::Skip if is_merge => continue,
+            MergeMode::Collapse if is_merge => collapse_branch(&repo, &commit, tickets)?,
+            _ => commit_info(&repo, &commit, tickets)?,
+        };
+        // A cherry-picked copy of a change on another branch is only counted
+        // once, as the older commit. The same change made again later on one
+        // line of history, e.g. after a revert, is new work.
+        let copy_of = info.patch_id.as_ref().and_then(|id| {
+            commits.iter().position(|c| c.patch_id.as_ref() == Some(id) && !on_one_line(&repo, c.oid, info.oid))
+        });
+        match copy_of {
+            Some(i) if info.time < commits[i].time => commits[i] = info,
+            Some(_) => {}
+            None => commits.push(info),
+        }
     }
 
-    commits.sort_by(|a, b| b.1.cmp(&a.1));
+    commits.sort_by_key(|c| std::cmp::Reverse(c.time));
 
     let mut activities = Vec::new();
     for i in 0..commits.len() {
-        let duration_minutes = if i + 1 < commits.len() {
-            let diff = commits[i].1 - commits[i + 1].1;
+        let duration_minutes = if let Some(span) = commits[i].span_minutes {
+            span
+        } else if i + 1 < commits.len() {
+            let diff = commits[i].time - commits[i + 1].time;
             std::cmp::max(diff.num_minutes() as u32, 1) // at least 1 min
         } else {
             1 // last commit fallback
@@ -40,18 +199,83 @@ pub fn get_github_activities(repo_path: &Path, max: usize) -> Result<Vec<Activit
 
         activities.push(Activity {
             id: (i + 1) as u32,
-            name: commits[i].0.clone(),
+            name: commits[i].message.clone(),
             duration_minutes,
-            date: commits[i].1.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+            date: commits[i].time.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+            tickets: commits[i].tickets.clone(),
+            participants: commits[i].participants.clone(),
+            patch_id: commits[i].patch_id.clone(),
+            source: Some(Source::git(&repo_dir, &commits[i].sha, &commits[i].author)),
+            project: project.clone(),
+            ..Default::default()
         });
     }
 
     Ok(activities)
 }
 
-pub fn sync_commits_to_file(repo_path: &Path, activities_path: &Path, max: usize) -> Result<()> {
-    let commits = get_github_activities(repo_path, max)?;
+pub fn sync_commits_to_file(
+    repo_path: &Path,
+    activities_path: &Path,
+    max: usize,
+    tickets: &TicketMatcher,
+    merges: MergeMode,
+) -> Result<()> {
+    let commits = get_github_activities(repo_path, max, tickets, merges)?;
+    append_commits_to_file(&open_repo(repo_path)?, commits, activities_path)
+}
+
+/// Records the commit at HEAD, e.g. from a `post-commit` hook. The previous
+/// commit is read too so the duration matches what `sync` would compute.
+pub fn record_head_commit(repo_path: &Path, activities_path: &Path, tickets: &TicketMatcher) -> Result<()> {
+    let commits = get_github_activities(repo_path, 2, tickets, MergeMode::default())?;
+    append_commits_to_file(&open_repo(repo_path)?, commits.into_iter().take(1).collect(), activities_path)
+}
+
+fn same_commit(a: &Activity, b: &Activity) -> bool {
+    match (a.source().and_then(|s| s.sha.as_ref()), b.source().and_then(|s| s.sha.as_ref())) {
+        (Some(x), Some(y)) => x == y,
+        _ => false,
+    }
+}
+
+/// Whether `stored` is a rebased or cherry-picked copy of `commit`: the same
+/// change under a commit that isn't on one line of history with it.
+fn copied_commit(repo: &Repository, stored: &Activity, commit: &Activity) -> bool {
+    if stored.patch_id.is_none() || stored.patch_id != commit.patch_id {
+        return false;
+    }
+    let oid = |a: &Activity| a.source()?.sha.as_deref().and_then(|sha| git2::Oid::from_str(sha).ok());
+    match (oid(stored), oid(commit)) {
+        (Some(a), Some(b)) => !on_one_line(repo, a, b),
+        _ => true,
+    }
+}
+
+/// Header and full message of the commit an activity was imported from,
+/// formatted like `git show --no-patch`.
+pub fn commit_details(source: &Source) -> Result<String> {
+    let (Some(repo_path), Some(sha)) = (&source.repo, &source.sha) else {
+        anyhow::bail!("Activity was not imported from a commit");
+    };
+    let repo = open_repo(Path::new(repo_path))?;
+    let oid = git2::Oid::from_str(sha).with_context(|| format!("Invalid commit id {}", sha))?;
+    let commit = repo.find_commit(oid)
+        .with_context(|| format!("Commit {} not found in {}", sha, repo_path))?;
+    let time = DateTime::from_timestamp(commit.time().seconds(), 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?
+        .with_timezone(&Local);
 
+    Ok(format!(
+        "commit {}\nAuthor: {}\nDate:   {}\n\n{}",
+        sha,
+        signature_name(&commit.author()),
+        time.format("%Y-%m-%d %H:%M"),
+        commit.message().unwrap_or("no message").trim_end(),
+    ))
+}
+
+fn append_commits_to_file(repo: &Repository, commits: Vec<Activity>, activities_path: &Path) -> Result<()> {
     let mut existing = if activities_path.exists() {
         Activity::load_from_file(activities_path)?
     } else {
@@ -59,17 +283,178 @@ pub fn sync_commits_to_file(repo_path: &Path, activities_path: &Path, max: usize
     };
 
     for commit in commits {
-        if !existing.iter().any(|a| a.name == commit.name && a.date == commit.date) {
+        let duplicate = existing.iter().any(|a| {
+            (a.name == commit.name && a.date == commit.date)
+                || copied_commit(repo, a, &commit)
+                || same_commit(a, &commit)
+        });
+        if !duplicate {
             let next_id = existing.iter().map(|a| a.id).max().unwrap_or(0) + 1;
-            existing.push(Activity {
-                id: next_id,
-                name: commit.name.clone(),
-                duration_minutes: commit.duration_minutes,
-                date: commit.date.clone(),
-            });
+            existing.push(Activity { id: next_id, ..commit });
         }
     }
 
     Activity::save_all_to_file(&existing, activities_path)?;
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Oid, Signature, Time};
+
+    struct TestRepo {
+        dir: tempfile::TempDir,
+        repo: Repository,
+    }
+
+    impl TestRepo {
+        fn new() -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            let repo = Repository::init(dir.path()).unwrap();
+            Self { dir, repo }
+        }
+
+        /// Commits at `minute` minutes past a fixed time on top of
+        /// `parents`, setting (`Some`) or deleting (`None`) files. Only
+        /// commits with `head` move HEAD.
+        fn commit(&self, minute: i64, message: &str, files: &[(&str, Option<&str>)], parents: &[Oid], head: bool) -> Oid {
+            let parents: Vec<git2::Commit> = parents.iter().map(|p| self.repo.find_commit(*p).unwrap()).collect();
+            let base = parents.first().map(|p| p.tree().unwrap());
+            let mut builder = self.repo.treebuilder(base.as_ref()).unwrap();
+            for (name, content) in files {
+                match content {
+                    Some(text) => {
+                        let blob = self.repo.blob(text.as_bytes()).unwrap();
+                        builder.insert(name, blob, 0o100644).unwrap();
+                    }
+                    None => builder.remove(name).unwrap(),
+                }
+            }
+            let tree = self.repo.find_tree(builder.write().unwrap()).unwrap();
+            let sig = Signature::new("Ana", "ana@example.com", &Time::new(1_790_000_000 + minute * 60, 0)).unwrap();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            self.repo
+                .commit(head.then_some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+                .unwrap()
+        }
+
+        fn import(&self, merges: MergeMode) -> Vec<Activity> {
+            let tickets = TicketMatcher::new(&[], &[]).unwrap();
+            get_github_activities(self.dir.path(), 100, &tickets, merges).unwrap()
+        }
+    }
+
+    fn names(activities: &[Activity]) -> Vec<&str> {
+        activities.iter().map(|a| a.name.lines().next().unwrap_or("")).collect()
+    }
+
+    /// `start`, then `feature 1` and `feature 2` on a branch, `main work`
+    /// on the main line, and a merge of the branch.
+    fn merged_history() -> TestRepo {
+        let t = TestRepo::new();
+        let start = t.commit(0, "start", &[("a.txt", Some("a"))], &[], true);
+        let f1 = t.commit(10, "feature 1", &[("b.txt", Some("b"))], &[start], false);
+        let f2 = t.commit(30, "feature 2", &[("b.txt", Some("bb"))], &[f1], false);
+        let main = t.commit(40, "main work", &[("a.txt", Some("aa"))], &[start], true);
+        let merge_tree = [("a.txt", Some("aa")), ("b.txt", Some("bb"))];
+        t.commit(60, "Merge feature", &merge_tree, &[main, f2], true);
+        t
+    }
+
+    #[test]
+    fn merge_modes() {
+        let t = merged_history();
+
+        assert_eq!(names(&t.import(MergeMode::Include)), vec!["Merge feature", "main work", "feature 2", "feature 1", "start"]);
+        assert_eq!(names(&t.import(MergeMode::Skip)), vec!["main work", "feature 2", "feature 1", "start"]);
+        assert_eq!(names(&t.import(MergeMode::FirstParent)), vec!["Merge feature", "main work", "start"]);
+
+        let collapsed = t.import(MergeMode::Collapse);
+        assert_eq!(names(&collapsed), vec!["Merge feature", "main work", "start"]);
+        // From the branch's first commit to the merge
+        assert_eq!(collapsed[0].duration_minutes, 50);
+    }
+
+    #[test]
+    fn merge_modes_with_an_octopus_merge() {
+        let t = TestRepo::new();
+        let start = t.commit(0, "start", &[("a.txt", Some("a"))], &[], true);
+        let one = t.commit(10, "branch one", &[("one.txt", Some("1"))], &[start], false);
+        let two = t.commit(20, "branch two", &[("two.txt", Some("2"))], &[start], false);
+        let three = t.commit(5, "branch three\n\nCo-authored-by: Bo <bo@example.com>", &[("three.txt", Some("3"))], &[start], false);
+        let main = t.commit(30, "main work", &[("a.txt", Some("aa"))], &[start], true);
+        let merged = [("a.txt", Some("aa")), ("one.txt", Some("1")), ("two.txt", Some("2")), ("three.txt", Some("3"))];
+        t.commit(60, "Merge branches", &merged, &[main, one, two, three], true);
+
+        assert_eq!(names(&t.import(MergeMode::Include)), vec!["Merge branches", "main work", "branch two", "branch one", "branch three", "start"]);
+        assert_eq!(names(&t.import(MergeMode::Skip)), vec!["main work", "branch two", "branch one", "branch three", "start"]);
+        assert_eq!(names(&t.import(MergeMode::FirstParent)), vec!["Merge branches", "main work", "start"]);
+
+        let collapsed = t.import(MergeMode::Collapse);
+        assert_eq!(names(&collapsed), vec!["Merge branches", "main work", "start"]);
+        // From the earliest commit on any merged branch, the third one
+        assert_eq!(collapsed[0].duration_minutes, 55);
+        assert_eq!(collapsed[0].participants, vec!["Ana <ana@example.com>", "Bo <bo@example.com>"]);
+    }
+
+    #[test]
+    fn reads_co_authors_once_per_email() {
+        let t = TestRepo::new();
+        let message = "Pair on the parser\n\n\
+            Co-authored-by: Bo <bo@example.com>\n\
+            Co-authored-by: Ana Lopez <ANA@example.com>\n\
+            Co-Authored-By: Bob <Bo@Example.com>\n\
+            Signed-off-by: Carla <carla@example.com>\n";
+        t.commit(0, message, &[("a.txt", Some("a"))], &[], true);
+
+        let imported = t.import(MergeMode::Include);
+        assert_eq!(imported[0].participants, vec!["Ana <ana@example.com>", "Bo <bo@example.com>"]);
+    }
+
+    #[test]
+    fn patch_id_dedupes_cherry_picks_but_not_repeated_work() {
+        let t = TestRepo::new();
+        let add = t.commit(0, "add file", &[("a.txt", Some("one\n"))], &[], true);
+        let remove = t.commit(10, "remove file", &[("a.txt", None)], &[add], true);
+        let again = t.commit(20, "add file again", &[("a.txt", Some("one\n"))], &[remove], true);
+        let fix = t.commit(30, "fix parser", &[("b.txt", Some("fix\n"))], &[again], true);
+        // The same fix cherry-picked onto a side branch, then merged
+        let picked = t.commit(40, "fix parser (cherry picked)", &[("b.txt", Some("fix\n"))], &[again], false);
+        let merged = [("a.txt", Some("one\n")), ("b.txt", Some("fix\n"))];
+        let merge = t.commit(50, "Merge side", &merged, &[fix, picked], true);
+        let marker = t.commit(60, "release marker", &[], &[merge], true);
+        t.commit(70, "another marker", &[], &[marker], true);
+
+        let imported = t.import(MergeMode::Include);
+        assert_eq!(names(&imported), vec![
+            "another marker", "release marker", "Merge side", "fix parser", "add file again", "remove file", "add file",
+        ]);
+        assert_eq!(imported[0].patch_id, None);
+        assert_eq!(imported[4].patch_id, imported[6].patch_id);
+    }
+
+    #[test]
+    fn sync_keeps_reapplied_changes_and_skips_rebased_copies() {
+        let t = TestRepo::new();
+        let file = t.dir.path().join("activities.json");
+        let tickets = TicketMatcher::new(&[], &[]).unwrap();
+        let sync = || sync_commits_to_file(t.dir.path(), &file, 100, &tickets, MergeMode::Include).unwrap();
+        let stored = || names(&Activity::load_from_file(&file).unwrap()).iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        let add = t.commit(0, "add file", &[("a.txt", Some("one\n"))], &[], true);
+        let remove = t.commit(10, "remove file", &[("a.txt", None)], &[add], true);
+        let fix = t.commit(20, "fix parser", &[("b.txt", Some("fix\n"))], &[remove], true);
+        sync();
+        assert_eq!(stored(), vec!["fix parser", "remove file", "add file"]);
+
+        // Rebase the fix onto a commit that adds the file again
+        let again = t.commit(30, "add file again", &[("a.txt", Some("one\n"))], &[remove], false);
+        let rebased = t.commit(40, "fix parser, rebased", &[("b.txt", Some("fix\n"))], &[again], false);
+        let branch = t.repo.head().unwrap().name().unwrap().to_string();
+        t.repo.reference(&branch, rebased, true, "rebase").unwrap();
+        assert_ne!(rebased, fix);
+        sync();
+        assert_eq!(stored(), vec!["fix parser", "remove file", "add file", "add file again"]);
+    }
+}
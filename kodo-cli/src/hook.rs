@@ -0,0 +1,260 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::git::open_repo;
+
+const BEGIN_MARKER: &str = "# >>> kodo post-commit >>>";
+const END_MARKER: &str = "# <<< kodo post-commit <<<";
+
+/// The `post-commit` hook git runs: in `core.hooksPath` if set, otherwise in
+/// the hooks directory shared by all worktrees.
+fn hook_path(repo_path: &Path) -> Result<PathBuf> {
+    let repo = open_repo(repo_path)?;
+    let hooks_path = repo.config()?.get_path("core.hooksPath").ok();
+    let dir = match hooks_path {
+        // Relative paths are relative to where hooks run: the top of the
+        // working tree, or the git directory of a bare repository
+        Some(dir) if dir.is_relative() => repo.workdir().unwrap_or(repo.path()).join(dir),
+        Some(dir) => dir,
+        None => common_dir(repo.path()).join("hooks"),
+    };
+    Ok(dir.join("post-commit"))
+}
+
+/// The git directory shared by all worktrees. A linked worktree's own git
+/// directory points to it in a `commondir` file.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => git_dir.join(common.trim_end()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', r"'\''"))
+}
+
+fn hook_block(activities_path: &Path, config_path: &Path) -> Result<String> {
+    let activities = std::path::absolute(activities_path)?;
+    let config = std::path::absolute(config_path)?;
+    Ok(format!(
+        "{BEGIN_MARKER}\n\
+         if command -v kodo >/dev/null 2>&1; then\n    \
+         kodo --file {} --config {} hook record . >/dev/null 2>&1 || true\n\
+         fi\n\
+         {END_MARKER}\n",
+        shell_quote(&activities),
+        shell_quote(&config),
+    ))
+}
+
+/// Whether a hook line ends the script, so nothing after it runs.
+fn ends_script(line: &str) -> bool {
+    let line = line.trim_start();
+    ["exit", "exec"].iter().any(|cmd| line == *cmd || line.starts_with(&format!("{cmd} ")))
+}
+
+/// `existing` hook with `block` added, or `None` if it is already there.
+/// The block goes before a final `exit` or `exec`, which would otherwise
+/// stop it from running.
+fn add_block(existing: &str, block: &str) -> Option<String> {
+    if existing.contains(BEGIN_MARKER) {
+        return None;
+    }
+    let body = existing.trim_end();
+    let last_line_start = body.rfind('\n').map_or(0, |i| i + 1);
+    let last_line = &body[last_line_start..];
+    if last_line_start > 0 && ends_script(last_line) {
+        let before = body[..last_line_start].trim_end();
+        return Some(format!("{before}\n\n{block}\n{last_line}\n"));
+    }
+    Some(format!("{body}\n\n{block}"))
+}
+
+/// `existing` hook without kodo's block, or `None` if it has none. Empty if
+/// nothing but the shebang is left.
+fn remove_block(existing: &str) -> Option<String> {
+    let start = existing.find(BEGIN_MARKER)?;
+    let end = existing.find(END_MARKER)?;
+    let before = existing[..start].trim_end();
+    let after = existing[end + END_MARKER.len()..].trim();
+    let rest = if after.is_empty() {
+        before.to_string()
+    } else {
+        format!("{before}\n\n{after}")
+    };
+    if rest.trim().is_empty() || rest.trim() == "#!/bin/sh" {
+        Some(String::new())
+    } else {
+        Some(rest + "\n")
+    }
+}
+
+/// Adds kodo's block to the repository's `post-commit` hook, creating the
+/// hook if needed. An existing hook is kept and the block is added to it.
+pub fn install(repo_path: &Path, activities_path: &Path, config_path: &Path) -> Result<()> {
+    let path = hook_path(repo_path)?;
+    let block = hook_block(activities_path, config_path)?;
+
+    let contents = if path.exists() {
+        let existing = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read existing hook {:?}", path))?;
+        let Some(contents) = add_block(&existing, &block) else {
+            println!("kodo post-commit hook is already installed in {:?}", path);
+            return Ok(());
+        };
+        contents
+    } else {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        format!("#!/bin/sh\n\n{block}")
+    };
+
+    std::fs::write(&path, contents)
+        .with_context(|| format!("Failed to write hook {:?}", path))?;
+    make_executable(&path)?;
+    println!("Installed post-commit hook in {:?}", path);
+    Ok(())
+}
+
+/// Removes kodo's block from the `post-commit` hook. The hook file is deleted
+/// only if nothing but the shebang is left.
+pub fn uninstall(repo_path: &Path) -> Result<()> {
+    let path = hook_path(repo_path)?;
+    let existing = if path.exists() {
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read hook {:?}", path))?
+    } else {
+        String::new()
+    };
+
+    let Some(rest) = remove_block(&existing) else {
+        println!("No kodo post-commit hook found in {:?}", path);
+        return Ok(());
+    };
+
+    if rest.is_empty() {
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove hook {:?}", path))?;
+    } else {
+        std::fs::write(&path, rest)
+            .with_context(|| format!("Failed to write hook {:?}", path))?;
+    }
+    println!("Removed kodo post-commit hook from {:?}", path);
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perms = std::fs::metadata(path)?.permissions();
+    perms.set_mode(perms.mode() | 0o755);
+    std::fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Repository;
+
+    const USER_HOOK: &str = "#!/bin/sh\nnpm run lint-staged\n";
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        dir
+    }
+
+    fn install_in(dir: &Path) {
+        install(dir, &dir.join("activities.json"), &dir.join("kodo.json")).unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn installs_and_uninstalls_a_new_hook() {
+        let dir = repo();
+        let hook = dir.path().join(".git/hooks/post-commit");
+
+        install_in(dir.path());
+        let installed = read(&hook);
+        assert!(installed.starts_with("#!/bin/sh\n\n# >>> kodo post-commit >>>\n"));
+        assert!(installed.contains("hook record . >/dev/null 2>&1 || true"));
+
+        // A second install leaves the hook as it is
+        install_in(dir.path());
+        assert_eq!(read(&hook), installed);
+
+        uninstall(dir.path()).unwrap();
+        assert!(!hook.exists());
+        // Uninstalling again finds nothing to remove
+        uninstall(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn keeps_an_existing_hook() {
+        let dir = repo();
+        let hook = dir.path().join(".git/hooks/post-commit");
+        std::fs::write(&hook, USER_HOOK).unwrap();
+
+        install_in(dir.path());
+        let installed = read(&hook);
+        assert!(installed.starts_with("#!/bin/sh\nnpm run lint-staged\n\n# >>> kodo"));
+        assert!(installed.ends_with("# <<< kodo post-commit <<<\n"));
+
+        uninstall(dir.path()).unwrap();
+        assert_eq!(read(&hook), USER_HOOK);
+    }
+
+    #[test]
+    fn adds_the_block_before_a_final_exit() {
+        let block = "# >>> kodo post-commit >>>\nkodo\n# <<< kodo post-commit <<<\n";
+        let hook = "#!/bin/sh\nnpm test\nexit 0\n";
+
+        let installed = add_block(hook, block).unwrap();
+        assert_eq!(installed, format!("#!/bin/sh\nnpm test\n\n{block}\nexit 0\n"));
+        assert_eq!(add_block(&installed, block), None);
+        assert_eq!(remove_block(&installed).unwrap(), "#!/bin/sh\nnpm test\n\nexit 0\n");
+
+        let exec = add_block("#!/bin/sh\nexec node hook.js \"$@\"", block).unwrap();
+        assert!(exec.ends_with(&format!("{block}\nexec node hook.js \"$@\"\n")));
+        // A lone `exit` line is not mistaken for a shebang-only hook
+        assert!(add_block("exit 0\n", block).unwrap().starts_with("exit 0\n\n# >>>"));
+        assert_eq!(remove_block(USER_HOOK), None);
+    }
+
+    #[test]
+    fn installs_worktree_hooks_in_the_main_repository() {
+        let dir = repo();
+        let main = Repository::open(dir.path()).unwrap();
+        let sig = git2::Signature::now("Ana", "ana@example.com").unwrap();
+        let tree = main.find_tree(main.treebuilder(None).unwrap().write().unwrap()).unwrap();
+        main.commit(Some("HEAD"), &sig, &sig, "start", &tree, &[]).unwrap();
+        let linked = dir.path().join("linked");
+        main.worktree("linked", &linked, None).unwrap();
+
+        install_in(&linked);
+        assert!(dir.path().join(".git/hooks/post-commit").exists());
+    }
+
+    #[test]
+    fn follows_core_hooks_path() {
+        let dir = repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.config().unwrap().set_str("core.hooksPath", ".githooks").unwrap();
+
+        install_in(dir.path());
+        assert!(dir.path().join(".githooks/post-commit").exists());
+        assert!(!dir.path().join(".git/hooks/post-commit").exists());
+    }
+}
@@ -1,12 +1,24 @@
-use clap::{Parser, Subcommand};
-use kodo_core::Activity;
+use clap::{Args, Parser, Subcommand};
+use kodo_core::{Activity, Config, Period};
+use std::io::IsTerminal;
 use std::path::Path;
 use anyhow::{Result, Context};
+use chrono::NaiveDate;
 
 mod git;
+mod hook;
+mod jira;
+mod worktree;
 mod tui;
 mod cli_actions;
+mod transfer;
+mod output;
 use crate::cli_actions::*;
+use crate::git::MergeMode;
+use crate::output::{OutputArgs, OutputFormat};
+use crate::transfer::{ExportFormat, ImportFormat, ImportOptions};
+use kodo_core::formats::csv::Mapping;
+use kodo_core::formats::ical::IcsFilter;
 
 #[derive(Parser, Debug)]
 #[command(name = "kodo", about = "A dev activity tracker CLI")]
@@ -16,11 +28,24 @@ struct Cli {
 
     #[arg(short, long)]
     file: Option<String>,
+
+    #[arg(short, long)]
+    config: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    Add { name: String, minutes: u32 },
+    Add {
+        name: String,
+        minutes: u32,
+        #[arg(long)]
+        project: Option<String>,
+        #[command(flatten)]
+        billable: BillableArgs,
+        /// Tag the activity, e.g. `--tag deep-work` (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
     Delete { id: u32 },
     Edit {
         id: u32,
@@ -28,36 +53,280 @@ enum Commands {
         name: Option<String>,
         #[arg(long)]
         minutes: Option<u32>,
+        #[arg(long)]
+        project: Option<String>,
+        #[command(flatten)]
+        billable: BillableArgs,
+        /// Replace the activity's tags (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    List {
+        /// Only show activities this person (name or email) worked on
+        #[arg(long)]
+        with: Option<String>,
+        #[command(flatten)]
+        output: OutputArgs,
     },
-    List,
     Filter {
         #[arg(long)]
         min: Option<u32>,
         #[arg(long)]
         max: Option<u32>,
+        #[command(flatten)]
+        output: OutputArgs,
     },
     Dashboard,
     Commits {
         #[arg(long, default_value_t = 5)]
         limit: usize,
+        #[arg(long, value_enum, default_value_t = MergeMode::Include)]
+        merges: MergeMode,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    Sync {
+        repo: String,
+        #[arg(long, value_enum, default_value_t = MergeMode::Include)]
+        merges: MergeMode,
+    },
+    /// Propose activities for uncommitted work in a repository
+    ScanWorktree {
+        #[arg(default_value = ".")]
+        repo: String,
+        /// Minutes without a file change that end a session
+        #[arg(long, default_value_t = 30)]
+        gap: u32,
+    },
+    Report {
+        #[arg(long)]
+        by_ticket: bool,
+        #[arg(long)]
+        by_person: bool,
+        /// Group activities by day, week or month
+        #[arg(long)]
+        period: Option<Period>,
+        /// Write a self-contained HTML report with charts to this file
+        #[arg(long, value_name = "FILE")]
+        html: Option<String>,
+        /// Days covered by --html: week, month, last-month or YYYY-MM-DD..YYYY-MM-DD
+        #[arg(long, default_value = "month", requires = "html")]
+        range: String,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Write a rounded timesheet as CSV plus Markdown or HTML
+    Timesheet {
+        /// First day (YYYY-MM-DD), defaults to the start of this month
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        #[arg(long)]
+        project: Option<String>,
+        /// Output path without extension
+        #[arg(long, default_value = "timesheet")]
+        out: String,
+        /// Write HTML instead of Markdown
+        #[arg(long)]
+        html: bool,
+    },
+    /// Bill a project's unbilled time for one month
+    Invoice {
+        #[arg(long)]
+        project: String,
+        /// Month to bill (YYYY-MM)
+        #[arg(long)]
+        month: String,
+        /// Output path without extension, defaults to the invoice number
+        #[arg(long)]
+        out: Option<String>,
+        /// Write HTML instead of Markdown
+        #[arg(long)]
+        html: bool,
+        /// Print the invoice without writing or recording it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show daily streaks and how consistently you log work
+    Streak {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Show duration statistics and time distributions
+    Stats {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Compare time spent in two periods, e.g. this week against last week
+    Compare {
+        /// today, week, month, last-week, last-month or YYYY-MM-DD..YYYY-MM-DD
+        #[arg(long = "this", default_value = "week")]
+        this: String,
+        /// Period to compare against, defaults to the one before `--this`
+        #[arg(long)]
+        vs: Option<String>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Summarise the previous working day as Markdown
+    Standup {
+        /// Day to summarise (YYYY-MM-DD), defaults to the previous weekday
+        #[arg(long)]
+        date: Option<NaiveDate>,
+        /// File with a custom layout, overriding `standup_template`
+        #[arg(long)]
+        template: Option<String>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Show progress towards the goals and budgets in the config
+    Goals {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Print a contribution heatmap of minutes per day
+    Heatmap {
+        /// Number of weeks shown, up to 520
+        #[arg(long, default_value_t = 52, value_parser = clap::value_parser!(u16).range(1..=kodo_core::heatmap::MAX_WEEKS as i64))]
+        weeks: u16,
+        /// Print without ANSI colours, as when output isn't a terminal
+        #[arg(long)]
+        no_color: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Write all activities in another format
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+        /// Output file, defaults to standard output
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Add activities from a file, skipping duplicates
+    Import {
+        file: String,
+        #[arg(long, value_enum, default_value_t = ImportFormat::Csv)]
+        format: ImportFormat,
+        /// Read a field from a column, e.g. `--map name=Description` or `--map minutes=3`
+        #[arg(long = "map", value_name = "FIELD=COLUMN")]
+        mappings: Vec<Mapping>,
+        /// Treat the first row as a header
+        #[arg(long, conflicts_with = "no_header")]
+        header: bool,
+        /// Treat the first row as data
+        #[arg(long)]
+        no_header: bool,
+        /// Column separator, e.g. ';'
+        #[arg(long)]
+        delimiter: Option<char>,
+        /// Only import calendar events mentioning this word (repeatable)
+        #[arg(long = "keyword")]
+        keywords: Vec<String>,
+        /// Only import calendar events in this category (repeatable)
+        #[arg(long = "category")]
+        categories: Vec<String>,
+        /// Minutes between heartbeats that end a session
+        #[arg(long, default_value_t = 15)]
+        timeout: u32,
+        /// Show what would be imported without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Log time against Jira issues
+    Jira {
+        #[command(subcommand)]
+        action: JiraAction,
+    },
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
     },
-    Sync { repo: String },
+}
+
+#[derive(Args, Debug)]
+struct BillableArgs {
+    /// Mark the activity as billable
+    #[arg(long, conflicts_with = "non_billable")]
+    billable: bool,
+    /// Mark the activity as internal, non-billable time
+    #[arg(long)]
+    non_billable: bool,
+}
+
+impl BillableArgs {
+    fn flag(&self) -> Option<bool> {
+        match (self.billable, self.non_billable) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum HookAction {
+    /// Install a post-commit hook that logs every new commit
+    Install {
+        #[arg(default_value = ".")]
+        repo: String,
+    },
+    /// Remove kodo's post-commit hook
+    Uninstall {
+        #[arg(default_value = ".")]
+        repo: String,
+    },
+    /// Record the HEAD commit (called by the hook)
+    #[command(hide = true)]
+    Record {
+        #[arg(default_value = ".")]
+        repo: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum JiraAction {
+    /// Queue worklogs for activities that reference Jira issues
+    Export {
+        /// First day (YYYY-MM-DD), defaults to the start of this month
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        #[arg(long)]
+        project: Option<String>,
+        /// Show the worklogs without queueing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Send queued worklogs to Jira
+    Submit,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
     let file_string = cli.file.clone().unwrap_or_else(|| "activities.json".to_string());
     let path = Path::new(&file_string);
+    let config_string = cli.config.clone().unwrap_or_else(|| "kodo.json".to_string());
+    let config = Config::load_from_file(Path::new(&config_string))
+        .with_context(|| format!("Failed to load config from {:?}", config_string))?;
+    let tickets = config.ticket_matcher()?;
 
     let mut activities = if path.exists() {
         let acts = Activity::load_from_file(path)
             .with_context(|| format!("Failed to load activities from {:?}", path))?;
         if acts.is_empty() {
-            println!("No activities found. Initializing empty list.");
+            eprintln!("No activities found. Initializing empty list.");
         }
         acts
+    } else if matches!(cli.command, Commands::Hook { action: HookAction::Install { .. } | HookAction::Uninstall { .. } }) {
+        // Setting up the hook doesn't touch activities
+        Vec::new()
     } else {
-        println!("activities.json not found. Creating a new one...");
+        eprintln!("activities.json not found. Creating a new one...");
         let acts = Vec::new();
         Activity::save_all_to_file(&acts, path)
             .with_context(|| format!("Failed to create {:?}", path))?;
@@ -65,30 +334,140 @@ fn main() -> Result<()> {
     };
 
     match cli.command {
-        Commands::Add { name, minutes } => {
-            add_activity(&mut activities, &name, minutes, path)?
+        Commands::Add { name, minutes, project, billable, tags } => {
+            add_activity(&mut activities, &name, minutes, project, billable.flag(), tags, &tickets, path)?
         }
         Commands::Delete { id } => delete_activity(&mut activities, id, path)?,
-        Commands::Edit { id, name, minutes } => {
-            edit_activity(&mut activities, id, name, minutes, path)?
+        Commands::Edit { id, name, minutes, project, billable, tags } => {
+            let tags = (!tags.is_empty()).then_some(tags);
+            edit_activity(&mut activities, id, name, minutes, project, billable.flag(), tags, &tickets, path)?
+        }
+        Commands::List { with, output: OutputArgs { output } } => match with {
+            Some(person) => {
+                let shared: Vec<Activity> = activities.iter()
+                    .filter(|a| a.involves(&person))
+                    .cloned()
+                    .collect();
+                list_activities(&shared, &config, output)?
+            }
+            None => list_activities(&activities, &config, output)?,
+        },
+        Commands::Filter { min, max, output: OutputArgs { output } } => {
+            filter_activities(&activities, min, max, &config, output)?
         }
-        Commands::List => list_activities(&activities),
-        Commands::Filter { min, max } => filter_activities(&activities, min, max),
         Commands::Dashboard => {
-            tui::run(&mut activities, path)?;
+            tui::run(&mut activities, path, &tickets, &config)?;
         }
-        Commands::Commits { limit } => {
-            let commits = git::get_github_activities(Path::new("."), limit)
+        Commands::Commits { limit, merges, output: OutputArgs { output } } => {
+            let commits = git::get_github_activities(Path::new("."), limit, &tickets, merges)
                 .context("Failed to fetch GitHub commits")?;
-            for act in commits {
-                println!("{} - {}", act.date, act.name);
+            if output != OutputFormat::Table {
+                output::print_activities(output, &commits, &config)?;
+            } else {
+                for act in commits {
+                    println!("{} - {}", act.date, act.name);
+                }
             }
         }
-        Commands::Sync { repo } => {
-            git::sync_commits_to_file(Path::new(&repo), path, 50)
+        Commands::Sync { repo, merges } => {
+            git::sync_commits_to_file(Path::new(&repo), path, 50, &tickets, merges)
                 .context("Failed to sync commits")?;
             println!("Commits synced into activities.json!");
         }
+        Commands::ScanWorktree { repo, gap } => {
+            let skip = [path, Path::new(&config_string)];
+            let drafts = worktree::scan(Path::new(&repo), chrono::Duration::minutes(gap as i64), &tickets, &skip)
+                .context("Failed to scan working tree")?;
+            review_drafts(&mut activities, drafts, &tickets, path)?;
+        }
+        Commands::Report { by_ticket, by_person, period, html, range, output: OutputArgs { output } } => {
+            if let Some(html) = &html {
+                write_html_report(&activities, &config, &range, html)?;
+            }
+            if output != OutputFormat::Table {
+                match (period, by_ticket, by_person) {
+                    (Some(period), false, false) => report_by_period(&activities, period, &config, output)?,
+                    (None, true, false) => report_by_ticket(&activities, &config, output)?,
+                    (None, false, true) => report_by_person(&activities, &config, output)?,
+                    _ => anyhow::bail!("Choose one of --period, --by-ticket or --by-person with --output"),
+                }
+                return Ok(());
+            }
+            if let Some(period) = period {
+                report_by_period(&activities, period, &config, output)?;
+            }
+            if by_ticket {
+                report_by_ticket(&activities, &config, output)?;
+            }
+            if by_person {
+                report_by_person(&activities, &config, output)?;
+            }
+            if period.is_none() && !by_ticket && !by_person && html.is_none() {
+                println!("Nothing to report. Try `kodo report --period week`, `--by-ticket` or `--by-person`.");
+            }
+        }
+        Commands::Timesheet { from, to, project, out, html } => {
+            let calendar = config.calendar()?;
+            let to = to.unwrap_or_else(|| calendar.today());
+            let from = from.unwrap_or_else(|| calendar.period_start(to, Period::Month));
+            write_timesheet(&activities, from, to, project.as_deref(), &config, &out, html)?;
+        }
+        Commands::Invoice { project, month, out, html, dry_run } => {
+            let from = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+                .with_context(|| format!("Invalid month {:?}, expected YYYY-MM", month))?;
+            let ledger_path = path.with_file_name("invoices.json");
+            let options = InvoiceOptions { out: out.as_deref(), html, dry_run };
+            create_invoice(&mut activities, path, &ledger_path, &project, from, &config, &options)?;
+        }
+        Commands::Streak { output: OutputArgs { output } } => show_streaks(&activities, &config, output)?,
+        Commands::Stats { output: OutputArgs { output } } => show_stats(&activities, &config, output)?,
+        Commands::Compare { this, vs, output: OutputArgs { output } } => {
+            compare_periods(&activities, &config, &this, vs.as_deref(), output)?
+        }
+        Commands::Standup { date, template, output: OutputArgs { output } } => {
+            print_standup(&activities, &config, date, template.as_deref(), output)?
+        }
+        Commands::Goals { output: OutputArgs { output } } => show_goals(&activities, &config, output)?,
+        Commands::Heatmap { weeks, no_color, output: OutputArgs { output } } => {
+            print_heatmap(&activities, &config, weeks as usize, !no_color && std::io::stdout().is_terminal(), output)?
+        }
+        Commands::Export { format, out } => {
+            transfer::export_activities(&activities, &config, format, out.as_deref())?
+        }
+        Commands::Import { file, format, mappings, header, no_header, delimiter, keywords, categories, timeout, dry_run } => {
+            let options = ImportOptions {
+                csv: transfer::csv_options(mappings, header, no_header, delimiter)?,
+                ics: IcsFilter { keywords, categories },
+                session_timeout: chrono::Duration::minutes(timeout as i64),
+                dry_run,
+            };
+            transfer::import_file(&mut activities, Path::new(&file), format, &options, &config, &tickets, path)?
+        }
+        Commands::Jira { action } => {
+            let queue_path = path.with_file_name("worklogs.json");
+            match action {
+                JiraAction::Export { from, to, project, dry_run } => {
+                    let calendar = config.calendar()?;
+                    let to = to.unwrap_or_else(|| calendar.today());
+                    let from = from.unwrap_or_else(|| calendar.period_start(to, Period::Month));
+                    export_worklogs(&activities, &queue_path, from, to, project.as_deref(), &config, dry_run)?
+                }
+                JiraAction::Submit => submit_worklogs(&queue_path, &config)?,
+            }
+        }
+        Commands::Hook { action } => match action {
+            HookAction::Install { repo } => {
+                hook::install(Path::new(&repo), path, Path::new(&config_string))
+                    .context("Failed to install post-commit hook")?
+            }
+            HookAction::Uninstall { repo } => {
+                hook::uninstall(Path::new(&repo)).context("Failed to uninstall post-commit hook")?
+            }
+            HookAction::Record { repo } => {
+                git::record_head_commit(Path::new(&repo), path, &tickets)
+                    .context("Failed to record commit")?
+            }
+        },
     }
 
     Ok(())
@@ -0,0 +1,50 @@
+//! Output formats for read commands. The records themselves live in
+//! [`kodo_core::output`].
+
+use anyhow::Result;
+use serde::Serialize;
+
+use kodo_core::output::{self, activity_output, Format, Record};
+use kodo_core::{Activity, Config};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Table,
+    /// One JSON document with items and totals
+    Json,
+    /// One JSON object per line, totals last
+    Jsonl,
+    /// Items as CSV
+    Csv,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct OutputArgs {
+    /// Output format for scripts
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+}
+
+/// Prints `items` and `totals` in a machine-readable `format`. Nothing is
+/// printed for [`OutputFormat::Table`]; commands print their own tables.
+pub fn print<R: Record, T: Serialize>(format: OutputFormat, items: &[R], totals: &T) -> Result<()> {
+    let format = match format {
+        OutputFormat::Table => return Ok(()),
+        OutputFormat::Json => Format::Json,
+        OutputFormat::Jsonl => Format::Jsonl,
+        OutputFormat::Csv => Format::Csv,
+    };
+    output::write(std::io::stdout().lock(), format, items, totals)
+}
+
+/// Activities with their totals, ready to print.
+pub fn print_activities<'a>(
+    format: OutputFormat,
+    activities: impl IntoIterator<Item = &'a Activity> + Clone,
+    config: &Config,
+) -> Result<()> {
+    let (items, totals) = activity_output(activities, config, &config.calendar()?);
+    print(format, &items, &totals)
+}
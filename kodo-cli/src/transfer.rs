@@ -0,0 +1,204 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use chrono::{Duration, NaiveDate, Utc};
+use clap::ValueEnum;
+use kodo_core::{Activity, Calendar, Config, Source, TicketMatcher};
+use kodo_core::formats::{self, ImportReport, ImportRow, ImportStatus};
+use kodo_core::formats::csv::{CsvOptions, Mapping};
+use kodo_core::formats::ical::IcsFilter;
+
+/// File formats `kodo import` reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    /// Comma-separated values, with or without a header
+    #[default]
+    Csv,
+    /// Toggl Track detailed report (CSV)
+    Toggl,
+    /// Clockify detailed report (CSV)
+    Clockify,
+    /// A Timewarrior data directory or a single `YYYY-MM.data` file
+    Timewarrior,
+    /// iCalendar events, filtered by `--keyword` and `--category`
+    Ics,
+    /// Org-mode `CLOCK:` lines, named after their heading
+    Org,
+    /// Heartbeat JSON from editor plugins or ActivityWatch, grouped into sessions
+    Heartbeats,
+}
+
+/// File formats `kodo export` writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Toggl,
+    Clockify,
+    /// Monthly `YYYY-MM.data` files; `--out` names the data directory
+    Timewarrior,
+    /// iCalendar, one event per activity
+    Ics,
+    /// Org-mode `CLOCK:` entries under a heading per day
+    Org,
+    /// Markdown daily notes with per-day totals
+    Markdown,
+}
+
+/// Format-specific options for `kodo import`.
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    pub csv: CsvOptions,
+    pub ics: IcsFilter,
+    /// Gap between heartbeats that ends a session.
+    pub session_timeout: Duration,
+    pub dry_run: bool,
+}
+
+pub fn export_activities(activities: &[Activity], config: &Config, format: ExportFormat, out: Option<&str>) -> Result<()> {
+    let text = match format {
+        ExportFormat::Csv => formats::csv::export(activities, config)?,
+        ExportFormat::Toggl => formats::toggl::export(activities, config)?,
+        ExportFormat::Clockify => formats::clockify::export(activities, config)?,
+        ExportFormat::Timewarrior => return export_timewarrior(activities, config, out),
+        ExportFormat::Ics => formats::ical::export(activities, &config.calendar()?, Utc::now()),
+        ExportFormat::Org => formats::org::export(activities, &config.calendar()?),
+        ExportFormat::Markdown => formats::markdown::export(activities, &config.calendar()?),
+    };
+    match out {
+        Some(out) => {
+            std::fs::write(out, text).with_context(|| format!("Failed to write {:?}", out))?;
+            println!("Exported {} activities to {}", activities.len(), out);
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Writes one file per month into the `out` directory, or everything to
+/// standard output.
+fn export_timewarrior(activities: &[Activity], config: &Config, out: Option<&str>) -> Result<()> {
+    let files = formats::timewarrior::export(activities, &config.calendar()?);
+    let Some(dir) = out else {
+        files.iter().for_each(|(_, contents)| print!("{}", contents));
+        return Ok(());
+    };
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    for (name, contents) in &files {
+        let file = Path::new(dir).join(name);
+        std::fs::write(&file, contents).with_context(|| format!("Failed to write {:?}", file))?;
+    }
+    println!("Exported {} activities to {} files in {}", activities.len(), files.len(), dir);
+    Ok(())
+}
+
+fn read_text(file: &Path) -> Result<String> {
+    std::fs::read_to_string(file).with_context(|| format!("Failed to read {:?}", file))
+}
+
+pub fn import_file(
+    activities: &mut Vec<Activity>,
+    file: &Path,
+    format: ImportFormat,
+    options: &ImportOptions,
+    config: &Config,
+    tickets: &TicketMatcher,
+    path: &Path,
+) -> Result<()> {
+    let calendar = config.calendar()?;
+    let mut rows = match format {
+        ImportFormat::Csv => formats::csv::import(&read_text(file)?, &options.csv, &calendar),
+        ImportFormat::Toggl => formats::toggl::import(&read_text(file)?, &calendar),
+        ImportFormat::Clockify => formats::clockify::import(&read_text(file)?, &calendar),
+        ImportFormat::Timewarrior => read_timewarrior(file, &calendar),
+        ImportFormat::Ics => Ok(formats::ical::import(&read_text(file)?, &options.ics, &calendar)),
+        ImportFormat::Org => Ok(formats::org::import(&read_text(file)?, &calendar)),
+        ImportFormat::Heartbeats => formats::heartbeats::import(&read_text(file)?, options.session_timeout, &calendar),
+    }
+    .with_context(|| format!("Failed to import {:?}", file))?;
+
+    let name = format.to_possible_value().expect("import formats are never skipped");
+    let source = Source::import(name.get_name());
+    for act in rows.iter_mut().filter_map(|r| r.result.as_mut().ok()) {
+        act.source = Some(source.clone());
+        if act.tickets.is_empty() {
+            act.tickets = tickets.extract(&act.name);
+        }
+    }
+
+    let report = ImportReport::build(activities, rows, &calendar);
+    for entry in &report.entries {
+        let what = entry.activity.as_ref()
+            .map(|a| format!("{} {} ({} min)", a.date, a.name.lines().next().unwrap_or(""), a.duration_minutes))
+            .unwrap_or_default();
+        match &entry.status {
+            ImportStatus::Added => println!("{:>10}: add       {}", entry.location(), what),
+            ImportStatus::Duplicate(reason) => println!("{:>10}: duplicate {} - {}", entry.location(), what, reason),
+            ImportStatus::Rejected(reason) => println!("{:>10}: rejected  {}", entry.location(), reason),
+        }
+    }
+
+    let (added, duplicates, rejected) = report.counts();
+    if options.dry_run {
+        println!("Dry run: {} would be added, {} duplicates, {} rejected.", added, duplicates, rejected);
+        return Ok(());
+    }
+    activities.extend(report.added().cloned());
+    Activity::save_all_to_file(activities, path)
+        .with_context(|| format!("Failed to save activities to {:?}", path))?;
+    println!("Imported {} activities ({} duplicates skipped, {} rejected).", added, duplicates, rejected);
+    Ok(())
+}
+
+/// Reads one data file, or every `YYYY-MM.data` file in a data directory.
+fn read_timewarrior(path: &Path, calendar: &Calendar) -> Result<Vec<ImportRow>> {
+    let now = Utc::now();
+    if !path.is_dir() {
+        return Ok(formats::timewarrior::import(&read_text(path)?, None, now, calendar));
+    }
+
+    let mut files: Vec<_> = std::fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory {:?}", path))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".data"))
+                .is_some_and(|month| NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").is_ok())
+        })
+        .collect();
+    files.sort();
+
+    let mut rows = Vec::new();
+    for file in files {
+        let name = file.file_name().map(|n| n.to_string_lossy().to_string());
+        rows.extend(formats::timewarrior::import(&read_text(&file)?, name.as_deref(), now, calendar));
+    }
+    Ok(rows)
+}
+
+/// Builds CSV options from the command line; `--header`/`--no-header`
+/// override header detection. The delimiter must be a single ASCII character.
+pub fn csv_options(mappings: Vec<Mapping>, header: bool, no_header: bool, delimiter: Option<char>) -> Result<CsvOptions> {
+    let has_header = match (header, no_header) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    if let Some(c) = delimiter.filter(|c| !c.is_ascii()) {
+        anyhow::bail!("The CSV delimiter must be an ASCII character, not {:?}", c);
+    }
+    Ok(CsvOptions { mappings, has_header, delimiter: delimiter.map(|c| c as u8) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delimiter_must_be_ascii() {
+        assert_eq!(csv_options(Vec::new(), false, false, Some(';')).unwrap().delimiter, Some(b';'));
+        let err = csv_options(Vec::new(), false, false, Some('§')).unwrap_err();
+        assert_eq!(err.to_string(), "The CSV delimiter must be an ASCII character, not '§'");
+    }
+}
@@ -9,24 +9,34 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout},
     widgets::Paragraph,
     Terminal,
 };
 use ratatui::prelude::{Backend, Style, Color, Modifier}; 
-use crate::tui::{input, widgets};
 use crate::git;
 use crate::tui::widgets::draw_dashboard;
-use kodo_core::Activity;
+use kodo_core::{Activity, Config, Source, TicketMatcher};
+use kodo_core::report::billable_split;
+use kodo_core::analytics::Analytics;
+use kodo_core::goals::GoalProgress;
+use kodo_core::heatmap::Heatmap;
+use kodo_core::streaks::StreakStats;
+use crate::tui::widgets::{StatsChart, StatsData};
 
-pub fn run(activities: &mut Vec<Activity>, path: &Path) -> io::Result<()> {
+pub fn run(
+    activities: &mut Vec<Activity>,
+    path: &Path,
+    tickets: &TicketMatcher,
+    config: &Config,
+) -> io::Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let res = run_app(&mut terminal, activities, path);
+    let res = run_app(&mut terminal, activities, path, tickets, config);
 
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
@@ -39,6 +49,8 @@ pub fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     activities: &mut Vec<Activity>,
     activities_path: &Path,
+    tickets: &TicketMatcher,
+    config: &Config,
 ) -> io::Result<()> {
     #[derive(PartialEq)]
     enum InputStage {
@@ -51,9 +63,9 @@ pub fn run_app<B: Backend>(
 
     #[derive(Debug, Clone, Copy, PartialEq)]
     enum SortMode {
-        ByDate,
-        ByDuration,
-        ByName,
+        Date,
+        Duration,
+        Name,
     }
 
     let mut input_stage = InputStage::Normal;
@@ -62,9 +74,13 @@ pub fn run_app<B: Backend>(
 
     let mut filter_min: Option<u32> = None;
     let mut filter_max: Option<u32> = None;
-    let mut sort_mode = SortMode::ByDate;
+    let mut sort_mode = SortMode::Date;
     let mut show_stats = false;
     let mut show_github = false;
+    let mut show_details = false;
+    let mut show_heatmap = false;
+    let mut stats_chart = StatsChart::default();
+    let mut details_cache: Option<(String, String)> = None; // (sha, text)
     let mut github_activities: Vec<Activity> = Vec::new();
 
     loop {
@@ -74,20 +90,53 @@ pub fn run_app<B: Backend>(
         } else {
             let mut filtered: Vec<Activity> = activities
                 .iter()
-                .filter(|a| filter_min.map_or(true, |m| a.duration_minutes >= m)
-                    && filter_max.map_or(true, |m| a.duration_minutes <= m))
+                .filter(|a| filter_min.is_none_or(|m| a.duration_minutes >= m)
+                    && filter_max.is_none_or(|m| a.duration_minutes <= m))
                 .cloned()
                 .collect();
 
             match sort_mode {
-                SortMode::ByDate => filtered.sort_by(|a, b| b.date.cmp(&a.date)),
-                SortMode::ByDuration => filtered.sort_by(|a, b| b.duration_minutes.cmp(&a.duration_minutes)),
-                SortMode::ByName => filtered.sort_by(|a, b| a.name.cmp(&b.name)),
+                SortMode::Date => filtered.sort_by(|a, b| b.date.cmp(&a.date)),
+                SortMode::Duration => filtered.sort_by_key(|a| std::cmp::Reverse(a.duration_minutes)),
+                SortMode::Name => filtered.sort_by(|a, b| a.name.cmp(&b.name)),
             }
 
             filtered
         };
 
+        let details: Option<String> = if show_details {
+            match view.get(selected).and_then(|a| a.source()).filter(|s| s.sha.is_some()) {
+                Some(source) => {
+                    let sha = source.sha.clone().unwrap_or_default();
+                    if details_cache.as_ref().is_none_or(|(cached, _)| *cached != sha) {
+                        let text = git::commit_details(source)
+                            .unwrap_or_else(|e| format!("Could not read commit {}:\n{:#}", sha, e));
+                        details_cache = Some((sha, text));
+                    }
+                    details_cache.as_ref().map(|(_, text)| text.clone())
+                }
+                None => Some("Not imported from a commit.".to_string()),
+            }
+        } else {
+            None
+        };
+
+        let stats: Option<StatsData> = if show_stats {
+            let calendar = config.calendar().unwrap_or_default();
+            Some(StatsData {
+                chart: stats_chart,
+                analytics: Analytics::compute(&view, &calendar),
+                streaks: StreakStats::compute(activities, &calendar, calendar.today()),
+                heatmap: show_heatmap.then(|| Heatmap::build(activities, &calendar, calendar.today(), 52)),
+                goals: config.goals
+                    .iter()
+                    .map(|goal| GoalProgress::compute(goal, activities, &calendar, calendar.now()))
+                    .collect(),
+            })
+        } else {
+            None
+        };
+
         terminal.draw(|f| {
             let size = f.size();
             let chunks = Layout::default()
@@ -112,23 +161,24 @@ pub fn run_app<B: Backend>(
             f.render_widget(subtitle, chunks[1]);
 
             let cmds = Paragraph::new(if show_github {
-                "q: quit | r: return to activities | v: toggle stats"
+                "q: quit | r: return to activities | v: toggle stats | i: commit details"
             } else {
-                "q: quit | a: add | d: delete | f: filter | r: reset filters | s: sort | v: toggle stats | g: git sync"
+                "q: quit | a: add | d: delete | f: filter | r: reset filters | s: sort | b: billable | v: toggle stats | c: chart | h: heatmap | i: commit details | g: git sync"
             })
             .style(Style::default().fg(Color::Yellow))
             .alignment(ratatui::layout::Alignment::Center);
             f.render_widget(cmds, chunks[2]);
 
-            draw_dashboard(f, chunks[3], &view, selected, show_stats);
+            draw_dashboard(f, chunks[3], &view, selected, stats.as_ref(), details.as_deref());
 
             let footer_text = match input_stage {
                 InputStage::Normal => {
                     let total: u32 = view.iter().map(|a| a.duration_minutes).sum();
+                    let (billable, internal) = billable_split(&view, config);
                     if show_github {
                         format!("GitHub commits: {} | Total est. time: {} min", view.len(), total)
                     } else {
-                        format!("Total shown: {} | Total time: {} min | filter(min={:?}, max={:?})", view.len(), total, filter_min, filter_max)
+                        format!("Total shown: {} | Total time: {} min (billable {} / non-billable {}) | filter(min={:?}, max={:?})", view.len(), total, billable, internal, filter_min, filter_max)
                     }
                 }
                 InputStage::AddingName => format!("Enter activity name: {}", input_buffer),
@@ -140,101 +190,111 @@ pub fn run_app<B: Backend>(
         })?;
 
         // Input handling
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match &mut input_stage {
-                    InputStage::Normal => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('a') => { input_stage = InputStage::AddingName; input_buffer.clear(); },
-                        KeyCode::Char('d') => {
-                            if !activities.is_empty() && !show_github {
-                                activities.remove(selected);
-                                if selected >= activities.len() && selected > 0 { selected -= 1; }
-                                Activity::save_all_to_file(activities, activities_path).ok();
-                            }
-                        }
-                        KeyCode::Char('f') => { input_stage = InputStage::FilteringMin; input_buffer.clear(); },
-                        KeyCode::Char('r') => {
-                            if show_github { show_github = false; } else { filter_min = None; filter_max = None; }
-                        }
-                        KeyCode::Char('s') => {
-                            sort_mode = match sort_mode {
-                                SortMode::ByDate => SortMode::ByDuration,
-                                SortMode::ByDuration => SortMode::ByName,
-                                SortMode::ByName => SortMode::ByDate,
-                            };
-                        }
-                        KeyCode::Char('g') => {
-                            let repo_path = std::env::current_dir().unwrap();
-                            github_activities = git::get_github_activities(&repo_path, 50).unwrap_or_default();
-                            show_github = true;
-                            selected = 0;
-                        }
-                        KeyCode::Up => { if selected > 0 { selected -= 1; } },
-                        KeyCode::Down => { if selected + 1 < view.len() { selected += 1; } },
-                        _ => {}
-                    },
-                    InputStage::AddingName => match key.code {
-                        KeyCode::Enter => {
-                            if !input_buffer.trim().is_empty() {
-                                let name = input_buffer.trim().to_string();
-                                input_buffer.clear();
-                                input_stage = InputStage::AddingDuration { name };
-                            }
-                        }
-                        KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
-                        KeyCode::Backspace => { input_buffer.pop(); },
-                        KeyCode::Char(c) => { input_buffer.push(c); },
-                        _ => {}
-                    },
-                    InputStage::AddingDuration { name } => match key.code {
-                        KeyCode::Enter => {
-                            let duration: u32 = input_buffer.trim().parse().unwrap_or(0);
-                            if duration > 0 {
-                                let next_id = activities.iter().map(|a| a.id).max().unwrap_or(0) + 1;
-                                activities.push(Activity {
-                                    id: next_id,
-                                    name: name.clone(),
-                                    duration_minutes: duration,
-                                    date: Local::now().format("%Y-%m-%d").to_string(),
-                                });
-                                Activity::save_all_to_file(activities, activities_path).ok();
-                            }
-                            input_buffer.clear();
-                            input_stage = InputStage::Normal;
-                        }
-                        KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
-                        KeyCode::Backspace => { input_buffer.pop(); },
-                        KeyCode::Char(c) => { input_buffer.push(c); },
-                        _ => {}
-                    },
-                    InputStage::FilteringMin => match key.code {
-                        KeyCode::Enter => {
-                            let min = input_buffer.trim().parse().unwrap_or(0);
-                            filter_min = if min > 0 { Some(min) } else { None };
-                            input_buffer.clear();
-                            input_stage = InputStage::FilteringMax { min };
+        if event::poll(std::time::Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()? {
+            match &mut input_stage {
+                InputStage::Normal => match key.code {
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char('a') => { input_stage = InputStage::AddingName; input_buffer.clear(); },
+                    KeyCode::Char('d') if !activities.is_empty() && !show_github => {
+                        activities.remove(selected);
+                        if selected >= activities.len() && selected > 0 { selected -= 1; }
+                        Activity::save_all_to_file(activities, activities_path).ok();
+                    }
+                    KeyCode::Char('f') => { input_stage = InputStage::FilteringMin; input_buffer.clear(); },
+                    KeyCode::Char('r') => {
+                        if show_github { show_github = false; } else { filter_min = None; filter_max = None; }
+                    }
+                    KeyCode::Char('s') => {
+                        sort_mode = match sort_mode {
+                            SortMode::Date => SortMode::Duration,
+                            SortMode::Duration => SortMode::Name,
+                            SortMode::Name => SortMode::Date,
+                        };
+                    }
+                    KeyCode::Char('v') => { show_stats = !show_stats; },
+                    KeyCode::Char('h') => { show_heatmap = !show_heatmap; show_stats = true; },
+                    KeyCode::Char('c') => { stats_chart = stats_chart.next(); show_heatmap = false; show_stats = true; },
+                    KeyCode::Char('b') if !show_github => {
+                        if let Some(id) = view.get(selected).map(|a| a.id)
+                            && let Some(act) = activities.iter_mut().find(|a| a.id == id)
+                        {
+                            act.billable = Some(!config.is_billable(act));
+                            Activity::save_all_to_file(activities, activities_path).ok();
                         }
-                        KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
-                        KeyCode::Backspace => { input_buffer.pop(); },
-                        KeyCode::Char(c) => { input_buffer.push(c); },
-                        _ => {}
-                    },
-                    InputStage::FilteringMax { min: _ } => match key.code {
-                        KeyCode::Enter => {
-                            let max = input_buffer.trim().parse().unwrap_or(0);
-                            filter_max = if max > 0 { Some(max) } else { None };
-                            input_buffer.clear();
-                            input_stage = InputStage::Normal;
+                    }
+                    KeyCode::Char('i') => { show_details = !show_details; },
+                    KeyCode::Char('g') => {
+                        let repo_path = std::env::current_dir().unwrap();
+                        github_activities = git::get_github_activities(&repo_path, 50, tickets, git::MergeMode::default()).unwrap_or_default();
+                        show_github = true;
+                        selected = 0;
+                    }
+                    KeyCode::Up => { selected = selected.saturating_sub(1); },
+                    KeyCode::Down if selected + 1 < view.len() => { selected += 1; },
+                    _ => {}
+                },
+                InputStage::AddingName => match key.code {
+                    KeyCode::Enter if !input_buffer.trim().is_empty() => {
+                        let name = input_buffer.trim().to_string();
+                        input_buffer.clear();
+                        input_stage = InputStage::AddingDuration { name };
+                    }
+                    KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
+                    KeyCode::Backspace => { input_buffer.pop(); },
+                    KeyCode::Char(c) => { input_buffer.push(c); },
+                    _ => {}
+                },
+                InputStage::AddingDuration { name } => match key.code {
+                    KeyCode::Enter => {
+                        let duration: u32 = input_buffer.trim().parse().unwrap_or(0);
+                        if duration > 0 {
+                            let next_id = activities.iter().map(|a| a.id).max().unwrap_or(0) + 1;
+                            activities.push(Activity {
+                                id: next_id,
+                                name: name.clone(),
+                                duration_minutes: duration,
+                                date: Local::now().format("%Y-%m-%d").to_string(),
+                                tickets: tickets.extract(name),
+                                source: Some(Source::manual()),
+                                ..Default::default()
+                            });
+                            Activity::save_all_to_file(activities, activities_path).ok();
                         }
-                        KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
-                        KeyCode::Backspace => { input_buffer.pop(); },
-                        KeyCode::Char(c) => { input_buffer.push(c); },
-                        _ => {}
+                        input_buffer.clear();
+                        input_stage = InputStage::Normal;
+                    }
+                    KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
+                    KeyCode::Backspace => { input_buffer.pop(); },
+                    KeyCode::Char(c) => { input_buffer.push(c); },
+                    _ => {}
+                },
+                InputStage::FilteringMin => match key.code {
+                    KeyCode::Enter => {
+                        let min = input_buffer.trim().parse().unwrap_or(0);
+                        filter_min = if min > 0 { Some(min) } else { None };
+                        input_buffer.clear();
+                        input_stage = InputStage::FilteringMax { min };
+                    }
+                    KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
+                    KeyCode::Backspace => { input_buffer.pop(); },
+                    KeyCode::Char(c) => { input_buffer.push(c); },
+                    _ => {}
+                },
+                InputStage::FilteringMax { min: _ } => match key.code {
+                    KeyCode::Enter => {
+                        let max = input_buffer.trim().parse().unwrap_or(0);
+                        filter_max = if max > 0 { Some(max) } else { None };
+                        input_buffer.clear();
+                        input_stage = InputStage::Normal;
+                    }
+                    KeyCode::Esc => { input_buffer.clear(); input_stage = InputStage::Normal; },
+                    KeyCode::Backspace => { input_buffer.pop(); },
+                    KeyCode::Char(c) => { input_buffer.push(c); },
+                    _ => {}
 
-                    },
-                }
+                },
             }
         }
     }
-}
\ No newline at end of file
+}
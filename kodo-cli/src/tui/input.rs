@@ -1,7 +1,7 @@
 use ratatui::widgets::{Block, Borders};
 use ratatui::style::{Color, Modifier, Style};
 
-pub fn header_block(title: &str) -> Block {
+pub fn header_block(title: &str) -> Block<'_> {
     Block::default()
         .title(title)
         .borders(Borders::ALL)
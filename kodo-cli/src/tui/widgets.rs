@@ -1,18 +1,57 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style, Modifier},
-    widgets::{Block, Borders, BarChart, Paragraph},
+    text::{Line, Span},
+    widgets::{Block, Borders, BarChart, LineGauge, Paragraph, Wrap},
     Frame,
 };
 use kodo_core::Activity;
+use kodo_core::analytics::Analytics;
+use kodo_core::goals::{GoalProgress, GoalStatus};
+use kodo_core::heatmap::{Heatmap, PALETTE};
+use kodo_core::timesheet::format_hours;
+use kodo_core::streaks::StreakStats;
+use crate::tui::input::header_block;
 use crate::tui::table::ActivityTable;
 
+/// What the stats view's bar chart shows; `c` cycles through these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsChart {
+    #[default]
+    Activities,
+    Durations,
+    Weekdays,
+    Hours,
+}
+
+impl StatsChart {
+    pub fn next(self) -> Self {
+        match self {
+            StatsChart::Activities => StatsChart::Durations,
+            StatsChart::Durations => StatsChart::Weekdays,
+            StatsChart::Weekdays => StatsChart::Hours,
+            StatsChart::Hours => StatsChart::Activities,
+        }
+    }
+}
+
+/// Everything the stats view shows besides the activity table.
+pub struct StatsData {
+    pub chart: StatsChart,
+    pub analytics: Analytics,
+    pub streaks: StreakStats,
+    /// Shown instead of the duration chart when set.
+    pub heatmap: Option<Heatmap>,
+    pub goals: Vec<GoalProgress>,
+}
+
 pub fn draw_dashboard(
     f: &mut Frame,
     area: Rect,
     activities: &[Activity],
     selected: usize,
-    show_stats: bool,
+    stats: Option<&StatsData>,
+    details: Option<&str>,
 ) {
     if activities.is_empty() {
         // Show friendly empty message
@@ -23,7 +62,7 @@ pub fn draw_dashboard(
         return;
     }
 
-    if show_stats {
+    if let Some(stats) = stats {
         // Split area: top for table, bottom for stats
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -31,36 +70,182 @@ pub fn draw_dashboard(
             .split(area);
 
         // Table
-        ActivityTable::draw(f, chunks[0], activities, selected);
+        draw_table(f, chunks[0], activities, selected, details);
 
-        // Stats: simple bar chart (text-based)
-        draw_stats(f, chunks[1], activities);
+        // Stats: bar chart with the streak panel (and goals, if any) beside it
+        let constraints = if stats.goals.is_empty() {
+            vec![Constraint::Percentage(65), Constraint::Percentage(35)]
+        } else {
+            vec![Constraint::Percentage(45), Constraint::Percentage(25), Constraint::Percentage(30)]
+        };
+        let stats_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .split(chunks[1]);
+        match &stats.heatmap {
+            Some(heatmap) => draw_heatmap(f, stats_chunks[0], heatmap),
+            None => draw_stats(f, stats_chunks[0], activities, stats),
+        }
+        draw_streaks(f, stats_chunks[1], &stats.streaks);
+        if !stats.goals.is_empty() {
+            draw_goals(f, stats_chunks[2], &stats.goals);
+        }
     } else {
         // Table only
-        ActivityTable::draw(f, area, activities, selected);
+        draw_table(f, area, activities, selected, details);
     }
 }
 
-fn draw_stats(f: &mut Frame, area: Rect, activities: &[Activity]) {
+fn draw_table(f: &mut Frame, area: Rect, activities: &[Activity], selected: usize, details: Option<&str>) {
+    let Some(details) = details else {
+        ActivityTable::draw(f, area, activities, selected);
+        return;
+    };
+
+    // Table on the left, commit details on the right
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    ActivityTable::draw(f, chunks[0], activities, selected);
+    let panel = Paragraph::new(details)
+        .block(header_block(" Commit "))
+        .wrap(Wrap { trim: false });
+    f.render_widget(panel, chunks[1]);
+}
+
+fn draw_stats(f: &mut Frame, area: Rect, activities: &[Activity], stats: &StatsData) {
     if activities.is_empty() {
         return;
     }
 
-    let mut data: Vec<(&str, u64)> = activities
-        .iter()
-        .map(|a| (a.name.as_str(), a.duration_minutes as u64))
-        .collect();
-
-    // sort descending by duration
-    data.sort_by(|a, b| b.1.cmp(&a.1));
+    let (title, mut data): (&str, Vec<(String, u64)>) = match stats.chart {
+        StatsChart::Activities => (
+            "Activity duration",
+            activities.iter().map(|a| (a.name.clone(), a.duration_minutes as u64)).collect(),
+        ),
+        StatsChart::Durations => (
+            "Activities per duration",
+            stats.analytics.buckets.iter().map(|(label, n)| (label.to_string(), *n as u64)).collect(),
+        ),
+        StatsChart::Weekdays => (
+            "Minutes per weekday",
+            stats.analytics.by_weekday.iter().map(|(w, m)| (w.to_string(), *m as u64)).collect(),
+        ),
+        StatsChart::Hours => (
+            "Minutes per hour",
+            stats.analytics.by_hour.iter().enumerate().map(|(h, m)| (format!("{:02}", h), *m as u64)).collect(),
+        ),
+    };
+    if stats.chart == StatsChart::Activities {
+        // sort descending by duration
+        data.sort_by_key(|d| std::cmp::Reverse(d.1));
+    }
+    let bars: Vec<(&str, u64)> = data.iter().map(|(label, value)| (label.as_str(), *value)).collect();
 
+    let d = &stats.analytics.durations;
+    let title = format!(
+        "{} | median {:.0} | p90 {:.0} | std dev {:.0} min",
+        title, d.median, d.p90, d.std_dev,
+    );
+    let bar_width = if stats.chart == StatsChart::Hours { 2 } else { 7 };
     let barchart = BarChart::default()
-        .block(Block::default().title("Activity Duration Stats").borders(Borders::ALL))
-        .data(&data)
-        .bar_width(7)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .data(&bars)
+        .bar_width(bar_width)
         .bar_style(Style::default().fg(Color::Cyan))
         .value_style(Style::default().fg(Color::Yellow))
-        .max(activities.iter().map(|a| a.duration_minutes).max().unwrap_or(1) as u64);
+        .max(bars.iter().map(|(_, v)| *v).max().unwrap_or(1).max(1));
 
     f.render_widget(barchart, area);
-}
\ No newline at end of file
+}
+
+fn draw_streaks(f: &mut Frame, area: Rect, streaks: &StreakStats) {
+    let recent: Vec<String> = streaks.weekly
+        .iter()
+        .rev()
+        .take(4)
+        .map(|(week, active)| format!("  {}  {}{} {}/7", week.format("%m-%d"), "#".repeat(*active as usize), ".".repeat(7 - *active as usize), active))
+        .collect();
+
+    let text = format!(
+        "Current streak: {} days\nLongest streak: {} days\nActive days: {} | Missed: {}\nActive days per week:\n{}",
+        streaks.current,
+        streaks.longest,
+        streaks.active_days,
+        streaks.missed_days.len(),
+        recent.join("\n"),
+    );
+    let panel = Paragraph::new(text)
+        .block(Block::default().title("Streaks").borders(Borders::ALL));
+    f.render_widget(panel, area);
+}
+
+/// Contribution heatmap, showing as many of the most recent weeks as fit.
+fn draw_heatmap(f: &mut Frame, area: Rect, heatmap: &Heatmap) {
+    let visible = ((area.width.saturating_sub(6)) / 2) as usize;
+    let first_week = heatmap.weeks.saturating_sub(visible);
+
+    let lines: Vec<Line> = (0..7)
+        .map(|row| {
+            let label = if row % 2 == 0 {
+                (heatmap.start + chrono::Days::new(row as u64)).format("%a").to_string()
+            } else {
+                String::new()
+            };
+            let mut spans = vec![Span::raw(format!("{:4}", label))];
+            for week in first_week..heatmap.weeks {
+                if let Some((_, minutes)) = heatmap.cell(week, row) {
+                    let color = Color::Indexed(PALETTE[heatmap.level(minutes)]);
+                    spans.push(Span::styled("■ ", Style::default().fg(color)));
+                }
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let title = format!("Minutes per day (busiest {} min)", heatmap.max_minutes());
+    let panel = Paragraph::new(lines)
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(panel, area);
+}
+
+/// Two gauges per goal: progress towards the target, and how much of the
+/// current day, week or month has already passed.
+fn draw_goals(f: &mut Frame, area: Rect, goals: &[GoalProgress]) {
+    let block = Block::default().title("Goals").borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); goals.len() * 2])
+        .split(inner);
+    for (i, progress) in goals.iter().enumerate() {
+        let color = match progress.status {
+            GoalStatus::Met | GoalStatus::OnTrack => Color::Green,
+            GoalStatus::AtRisk => Color::Yellow,
+            GoalStatus::Over => Color::Red,
+        };
+        let target = LineGauge::default()
+            .label(format!(
+                "{} {}/{}",
+                progress.goal.name,
+                format_hours(progress.minutes),
+                format_hours(progress.goal.minutes),
+            ))
+            .gauge_style(Style::default().fg(color))
+            .ratio(progress.ratio().min(1.0));
+        let elapsed = LineGauge::default()
+            .label(format!("  {} {:.0}%", progress.goal.window_name(), progress.elapsed * 100.0))
+            .gauge_style(Style::default().fg(Color::DarkGray))
+            .ratio(progress.elapsed);
+        if let Some(row) = rows.get(i * 2) {
+            f.render_widget(target, *row);
+        }
+        if let Some(row) = rows.get(i * 2 + 1) {
+            f.render_widget(elapsed, *row);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, SubsecRound};
+use git2::StatusOptions;
+use std::path::Path;
+
+use kodo_core::sessions::{group_sessions, start_first_at};
+use kodo_core::{Activity, Source, TicketMatcher};
+
+use crate::git::{open_repo, repo_project};
+
+/// Infers editing sessions from uncommitted changes in the working tree.
+///
+/// Every changed or untracked file modified after the HEAD commit is an
+/// event at its modification time; events closer than `gap` form one
+/// session, which becomes a draft activity. Files in `skip` (kodo's own
+/// data files) are not counted as edits.
+///
+/// Only each file's last modification is known, so a session can't see
+/// how long a file was worked on. The first session is taken to start at
+/// the HEAD commit if that was the same day; otherwise, and for later
+/// sessions, the duration is a lower bound.
+pub fn scan(repo_path: &Path, gap: Duration, tickets: &TicketMatcher, skip: &[&Path]) -> Result<Vec<Activity>> {
+    let repo = open_repo(repo_path)?;
+    let workdir = repo.workdir()
+        .ok_or_else(|| anyhow::anyhow!("Repository at {:?} has no working tree", repo_path))?
+        .to_path_buf();
+    let skip: Vec<_> = skip.iter().filter_map(|p| std::fs::canonicalize(p).ok()).collect();
+
+    let head = repo.head().ok();
+    let branch = head.as_ref()
+        .and_then(|h| h.shorthand())
+        .unwrap_or("HEAD")
+        .to_string();
+    let last_commit: Option<DateTime<Local>> = head
+        .and_then(|h| h.peel_to_commit().ok())
+        .and_then(|c| DateTime::from_timestamp(c.time().seconds(), 0))
+        .map(|t| t.with_timezone(&Local));
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut opts)).context("Failed to read working tree status")?;
+
+    let mut events = Vec::new();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.is_wt_deleted() || status.is_index_deleted() {
+            continue;
+        }
+        let Some(file) = entry.path() else { continue };
+        let full_path = workdir.join(file);
+        if std::fs::canonicalize(&full_path).is_ok_and(|p| skip.contains(&p)) {
+            continue;
+        }
+        let Ok(modified) = std::fs::metadata(&full_path).and_then(|m| m.modified()) else {
+            continue;
+        };
+        let modified = DateTime::<Local>::from(modified).trunc_subsecs(0);
+        if last_commit.is_some_and(|since| modified <= since) {
+            continue;
+        }
+        events.push((modified, modified, file.to_string()));
+    }
+
+    let repo_dir = workdir.display().to_string();
+    let project = repo_project(&repo);
+    let mut sessions = group_sessions(events, gap);
+    if let Some(since) = last_commit {
+        start_first_at(&mut sessions, since);
+    }
+    let drafts = sessions
+        .into_iter()
+        .map(|session| {
+            let name = format!("Uncommitted work on {}: {}", branch, summarize_files(&session.items));
+            Activity {
+                name: name.clone(),
+                duration_minutes: session.minutes(),
+                date: session.start.format("%Y-%m-%d").to_string(),
+                started_at: Some(session.start.fixed_offset()),
+                tickets: tickets.extract(&name),
+                project: project.clone(),
+                source: Some(Source::worktree(&repo_dir)),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Ok(drafts)
+}
+
+fn summarize_files(files: &[String]) -> String {
+    let mut names: Vec<&str> = files.iter().map(|f| f.as_str()).collect();
+    names.dedup();
+    if names.len() > 3 {
+        format!("{} (+{} more)", names[..3].join(", "), names.len() - 3)
+    } else {
+        names.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, TimeZone};
+    use git2::{Repository, Signature, Time};
+    use kodo_core::SourceKind;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 14, hour, minute, 0).unwrap()
+    }
+
+    /// Writes `file` under `dir` and sets its modification time.
+    fn touch(dir: &Path, file: &str, modified: DateTime<Local>) {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, file).unwrap();
+        let handle = std::fs::File::options().write(true).open(&path).unwrap();
+        handle.set_modified(modified.into()).unwrap();
+    }
+
+    #[test]
+    fn scans_sessions_from_modification_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        // One committed file, left unchanged, and one edited before the commit
+        touch(dir.path(), "old.rs", at(8, 0));
+        touch(dir.path(), "early.rs", at(8, 0));
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("old.rs")).unwrap();
+        index.add_path(Path::new("early.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::new("Ana", "ana@example.com", &Time::new(at(9, 0).timestamp(), 0)).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "start", &tree, &[]).unwrap();
+        touch(dir.path(), "early.rs", at(8, 30));
+
+        touch(dir.path(), "src/lib.rs", at(9, 40));
+        touch(dir.path(), "src/main.rs", at(9, 50));
+        // Would join the two sessions if it counted as an edit
+        touch(dir.path(), "activities.json", at(10, 10));
+        touch(dir.path(), "PAY-7.md", at(10, 35));
+
+        let tickets = TicketMatcher::new(&[r"\bPAY-\d+\b".to_string()], &[]).unwrap();
+        let skip = [dir.path().join("activities.json")];
+        let skip: Vec<&Path> = skip.iter().map(|p| p.as_path()).collect();
+        let drafts = scan(dir.path(), Duration::minutes(30), &tickets, &skip).unwrap();
+
+        let summary: Vec<(DateTime<FixedOffset>, u32)> = drafts
+            .iter()
+            .map(|d| (d.started_at.unwrap(), d.duration_minutes))
+            .collect();
+        // The first session starts at the commit, the second is a lower bound
+        assert_eq!(summary, vec![(at(9, 0).fixed_offset(), 50), (at(10, 35).fixed_offset(), 1)]);
+        assert!(drafts[0].name.ends_with(": src/lib.rs, src/main.rs"));
+        assert_eq!(drafts[1].tickets, vec!["PAY-7"]);
+        assert!(drafts.iter().all(|d| d.source.as_ref().is_some_and(|s| s.kind == SourceKind::Worktree && s.sha.is_none())));
+    }
+}
@@ -3,14 +3,97 @@ use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 use anyhow::{Result, Context};
-use chrono::Local;
+use chrono::{DateTime, FixedOffset, Local};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::tickets::TicketMatcher;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Activity {
     pub id: u32,
     pub name: String,
     pub duration_minutes: u32,
     pub date: String,
+    /// When the work started, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<FixedOffset>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Explicit billable flag; when unset the project's default applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub billable: Option<bool>,
+    /// Number of the invoice this activity was billed on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tickets: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub participants: Vec<String>,
+    /// Git patch id of an imported commit, used to spot rebased duplicates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    #[default]
+    Manual,
+    Git,
+    /// A draft proposed from uncommitted changes in a working tree.
+    Worktree,
+    /// Read from another tool's export by `kodo import`.
+    Import,
+}
+
+/// Where an activity came from. Commit-derived activities keep enough to
+/// find the commit again; imported ones name the format they were read from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Source {
+    pub kind: SourceKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+impl Source {
+    pub fn manual() -> Self {
+        Self::default()
+    }
+
+    pub fn git(repo: &str, sha: &str, author: &str) -> Self {
+        Self {
+            kind: SourceKind::Git,
+            repo: Some(repo.to_string()),
+            sha: Some(sha.to_string()),
+            author: Some(author.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn worktree(repo: &str) -> Self {
+        Self {
+            kind: SourceKind::Worktree,
+            repo: Some(repo.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn import(format: &str) -> Self {
+        Self {
+            kind: SourceKind::Import,
+            format: Some(format.to_string()),
+            ..Default::default()
+        }
+    }
 }
 
 impl Activity {
@@ -22,12 +105,35 @@ impl Activity {
             name: name.to_string(),
             duration_minutes,
             date: today,
+            source: Some(Source::manual()),
+            ..Default::default()
         }
     }
 
     pub fn id(&self) -> u32 { self.id }
     pub fn name(&self) -> &str { &self.name }
     pub fn duration_minutes(&self) -> u32 { self.duration_minutes }
+    pub fn project(&self) -> Option<&str> { self.project.as_deref() }
+    pub fn tags(&self) -> &[String] { &self.tags }
+    pub fn tickets(&self) -> &[String] { &self.tickets }
+    pub fn participants(&self) -> &[String] { &self.participants }
+    pub fn source(&self) -> Option<&Source> { self.source.as_ref() }
+
+    /// Changes the name and re-reads the ticket references from it.
+    pub fn rename(&mut self, name: &str, tickets: &TicketMatcher) {
+        self.name = name.to_string();
+        self.tickets = tickets.extract(name);
+    }
+
+    pub fn is_from_git(&self) -> bool {
+        self.source.as_ref().is_some_and(|s| s.kind == SourceKind::Git)
+    }
+
+    /// Whether `person` (a case-insensitive part of a name or email) worked on this activity.
+    pub fn involves(&self, person: &str) -> bool {
+        let person = person.to_lowercase();
+        self.participants.iter().any(|p| p.to_lowercase().contains(&person))
+    }
 
     pub fn save_all_to_file(activities: &[Activity], path: &Path) -> Result<()> {
         let json = serde_json::to_string_pretty(activities)?; 
@@ -55,3 +161,24 @@ impl Activity {
 }
 
 }
+
+/// Who a participant such as `Ana Lopez <Ana@Example.com>` is: the lowercased
+/// email, or the lowercased name when there is no email. The same person may
+/// be written with a different name or casing in different commits.
+pub fn identity(participant: &str) -> String {
+    let email = participant
+        .rsplit_once('<')
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map(|(email, _)| email.trim())
+        .filter(|email| !email.is_empty());
+    email.unwrap_or(participant.trim()).to_lowercase()
+}
+
+/// Adds `person` to `people` unless someone with the same [`identity`] is
+/// already there.
+pub fn add_participant(people: &mut Vec<String>, person: String) {
+    let id = identity(&person);
+    if !people.iter().any(|p| identity(p) == id) {
+        people.push(person);
+    }
+}
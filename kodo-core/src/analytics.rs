@@ -0,0 +1,171 @@
+use chrono::{Datelike, Duration, Timelike, Weekday};
+use serde::Serialize;
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+
+/// Upper bounds (exclusive, in minutes) and labels of the duration buckets.
+/// The last bucket is open-ended.
+const BUCKETS: [(u32, &str); 6] = [
+    (15, "<15m"),
+    (30, "15-30m"),
+    (60, "30-60m"),
+    (120, "1-2h"),
+    (240, "2-4h"),
+    (u32::MAX, "4h+"),
+];
+
+/// Summary statistics over a set of durations, in minutes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DurationStats {
+    pub count: usize,
+    pub total: u32,
+    pub mean: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub p95: f64,
+    /// Population standard deviation.
+    pub std_dev: f64,
+    pub min: u32,
+    pub max: u32,
+}
+
+impl DurationStats {
+    pub fn compute(durations: &[u32]) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = durations.to_vec();
+        sorted.sort_unstable();
+        let total: u32 = sorted.iter().sum();
+        let mean = total as f64 / sorted.len() as f64;
+        let variance = sorted.iter().map(|&d| (d as f64 - mean).powi(2)).sum::<f64>() / sorted.len() as f64;
+
+        Self {
+            count: sorted.len(),
+            total,
+            mean,
+            median: percentile(&sorted, 50.0),
+            p75: percentile(&sorted, 75.0),
+            p90: percentile(&sorted, 90.0),
+            p95: percentile(&sorted, 95.0),
+            std_dev: variance.sqrt(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// The `p`th percentile (0 to 100) of sorted values, interpolating linearly
+/// between the closest ranks.
+pub fn percentile(sorted: &[u32], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0] as f64,
+        n => {
+            let rank = p.clamp(0.0, 100.0) / 100.0 * (n - 1) as f64;
+            let low = rank.floor() as usize;
+            let high = rank.ceil() as usize;
+            let weight = rank - low as f64;
+            sorted[low] as f64 + (sorted[high] as f64 - sorted[low] as f64) * weight
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagStats {
+    pub tag: String,
+    pub durations: DurationStats,
+}
+
+/// Distributions and summary statistics for a set of activities.
+#[derive(Debug, Clone)]
+pub struct Analytics {
+    pub durations: DurationStats,
+    /// Ids of the longest and shortest activities.
+    pub longest: Option<u32>,
+    pub shortest: Option<u32>,
+    /// Activity counts per duration bucket, shortest bucket first.
+    pub buckets: Vec<(&'static str, usize)>,
+    /// Minutes per hour of the day, for activities with a start time. An
+    /// activity spanning several hours is split between them.
+    pub by_hour: [u32; 24],
+    /// Minutes per weekday, starting with the calendar's first day of the week.
+    pub by_weekday: Vec<(Weekday, u32)>,
+    /// Largest total first.
+    pub by_tag: Vec<TagStats>,
+}
+
+impl Analytics {
+    pub fn compute(activities: &[Activity], calendar: &Calendar) -> Self {
+        let durations: Vec<u32> = activities.iter().map(|a| a.duration_minutes).collect();
+
+        let mut buckets: Vec<(&'static str, usize)> = BUCKETS.iter().map(|(_, label)| (*label, 0)).collect();
+        for &minutes in &durations {
+            let index = BUCKETS.iter().position(|(limit, _)| minutes < *limit).unwrap_or(BUCKETS.len() - 1);
+            buckets[index].1 += 1;
+        }
+
+        let mut by_hour = [0; 24];
+        for act in activities {
+            let Some(started) = act.started_at else { continue };
+            let mut at = calendar.to_local(started);
+            let mut left = act.duration_minutes;
+            while left > 0 {
+                let in_hour = (60 - at.minute()).min(left);
+                by_hour[at.hour() as usize] += in_hour;
+                left -= in_hour;
+                at += Duration::minutes(in_hour as i64);
+            }
+        }
+
+        let mut by_weekday: Vec<(Weekday, u32)> = Vec::new();
+        let mut weekday = calendar.week_start;
+        for _ in 0..7 {
+            by_weekday.push((weekday, 0));
+            weekday = weekday.succ();
+        }
+        for act in activities {
+            if let Some(day) = calendar.day_of(act)
+                && let Some(entry) = by_weekday.iter_mut().find(|(w, _)| *w == day.weekday())
+            {
+                entry.1 += act.duration_minutes;
+            }
+        }
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in activities.iter().flat_map(|a| a.tags()) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        let mut by_tag: Vec<TagStats> = tags
+            .into_iter()
+            .map(|tag| {
+                let tagged: Vec<u32> = activities
+                    .iter()
+                    .filter(|a| a.tags().contains(&tag))
+                    .map(|a| a.duration_minutes)
+                    .collect();
+                TagStats { durations: DurationStats::compute(&tagged), tag }
+            })
+            .collect();
+        by_tag.sort_by(|a, b| b.durations.total.cmp(&a.durations.total).then_with(|| a.tag.cmp(&b.tag)));
+
+        Self {
+            durations: DurationStats::compute(&durations),
+            longest: activities.iter().max_by_key(|a| a.duration_minutes).map(|a| a.id),
+            shortest: activities.iter().min_by_key(|a| a.duration_minutes).map(|a| a.id),
+            buckets,
+            by_hour,
+            by_weekday,
+            by_tag,
+        }
+    }
+
+    /// Whether any activity had a start time, so `by_hour` means something.
+    pub fn has_times(&self) -> bool {
+        self.by_hour.iter().any(|&m| m > 0)
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Datelike, Days, FixedOffset, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Weekday};
+use anyhow::{Result, Context, bail};
+use serde::{Serialize, Deserialize};
+use std::str::FromStr;
+
+use crate::activity::Activity;
+
+/// A calendar grouping for reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl FromStr for Period {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            other => bail!("Unknown period {:?} (expected day, week or month)", other),
+        }
+    }
+}
+
+/// Decides which calendar day an activity belongs to and where weeks start.
+#[derive(Debug, Clone, Copy)]
+pub struct Calendar {
+    pub week_start: Weekday,
+    /// Fixed offset to use for timestamps; `None` means the local timezone.
+    pub offset: Option<FixedOffset>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self { week_start: Weekday::Mon, offset: None }
+    }
+}
+
+impl Calendar {
+    /// Builds a calendar from config values: a weekday name and either
+    /// `local`, `UTC` or an offset such as `+05:30`.
+    pub fn new(week_start: &str, timezone: &str) -> Result<Self> {
+        let week_start = Weekday::from_str(week_start)
+            .map_err(|_| anyhow::anyhow!("Invalid week start {:?}", week_start))?;
+        let offset = match timezone.to_ascii_lowercase().as_str() {
+            "local" | "" => None,
+            "utc" | "z" => Some(FixedOffset::east_opt(0).unwrap()),
+            _ => Some(parse_offset(timezone)?),
+        };
+        Ok(Self { week_start, offset })
+    }
+
+    /// Today's date in this calendar's timezone.
+    pub fn today(&self) -> NaiveDate {
+        self.now().date()
+    }
+
+    /// The current wall-clock time in this calendar's timezone.
+    pub fn now(&self) -> NaiveDateTime {
+        match self.offset {
+            Some(offset) => Local::now().with_timezone(&offset).naive_local(),
+            None => Local::now().naive_local(),
+        }
+    }
+
+    /// Attaches this calendar's timezone to a wall-clock time. `None` for
+    /// times skipped by a daylight saving change.
+    pub fn localize(&self, naive: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self.offset {
+            Some(offset) => offset.from_local_datetime(&naive).single(),
+            None => Local.from_local_datetime(&naive).earliest().map(|t| t.fixed_offset()),
+        }
+    }
+
+    /// Converts a timestamp into this calendar's timezone.
+    pub fn to_local(&self, time: DateTime<FixedOffset>) -> NaiveDateTime {
+        match self.offset {
+            Some(offset) => time.with_timezone(&offset).naive_local(),
+            None => time.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// The day an activity happened on. The start time wins over the stored
+    /// date when both are known, so the configured timezone applies.
+    pub fn day_of(&self, activity: &Activity) -> Option<NaiveDate> {
+        if let Some(started) = activity.started_at {
+            return Some(self.to_local(started).date());
+        }
+        NaiveDate::parse_from_str(&activity.date, "%Y-%m-%d").ok()
+    }
+
+    /// First day of the period containing `day`.
+    pub fn period_start(&self, day: NaiveDate, period: Period) -> NaiveDate {
+        match period {
+            Period::Day => day,
+            Period::Week => day.week(self.week_start).first_day(),
+            Period::Month => day.with_day(1).unwrap(),
+        }
+    }
+
+    /// First day after the period starting at `start`.
+    pub fn period_end(&self, start: NaiveDate, period: Period) -> NaiveDate {
+        match period {
+            Period::Day => start + Days::new(1),
+            Period::Week => start + Days::new(7),
+            Period::Month => start + Months::new(1),
+        }
+    }
+}
+
+fn parse_offset(s: &str) -> Result<FixedOffset> {
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'+') => (1, &s[1..]),
+        Some(b'-') => (-1, &s[1..]),
+        _ => bail!("Invalid timezone {:?} (expected local, UTC or an offset like +05:30)", s),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().with_context(|| format!("Invalid timezone {:?}", s))?;
+    let minutes: i32 = minutes.parse().with_context(|| format!("Invalid timezone {:?}", s))?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .ok_or_else(|| anyhow::anyhow!("Timezone offset out of range: {:?}", s))
+}
@@ -0,0 +1,155 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use anyhow::{Result, Context, bail};
+
+use crate::activity::Activity;
+use crate::calendar::{Calendar, Period};
+use crate::timesheet::NO_PROJECT;
+
+/// An inclusive range of days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl DateRange {
+    /// Parses `today`, `yesterday`, `week`/`this-week`, `last-week`,
+    /// `month`/`this-month`, `last-month` or a custom `YYYY-MM-DD..YYYY-MM-DD`.
+    pub fn parse(spec: &str, calendar: &Calendar, today: NaiveDate) -> Result<Self> {
+        let whole = |day: NaiveDate, period: Period| {
+            let from = calendar.period_start(day, period);
+            Self { from, to: calendar.period_end(from, period) - Days::new(1) }
+        };
+        let range = match spec.to_ascii_lowercase().as_str() {
+            "today" | "day" | "this-day" => whole(today, Period::Day),
+            "yesterday" | "last-day" => whole(today - Days::new(1), Period::Day),
+            "week" | "this-week" => whole(today, Period::Week),
+            "last-week" => whole(today - Days::new(7), Period::Week),
+            "month" | "this-month" => whole(today, Period::Month),
+            "last-month" => whole(calendar.period_start(today, Period::Month) - Days::new(1), Period::Month),
+            _ => {
+                let Some((from, to)) = spec.split_once("..") else {
+                    bail!("Unknown range {:?} (expected e.g. week, last-month or 2026-10-01..2026-10-07)", spec);
+                };
+                let parse = |s: &str| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date {:?} in range {:?}", s, spec));
+                let range = Self { from: parse(from)?, to: parse(to)? };
+                if range.to < range.from {
+                    bail!("Range {:?} ends before it starts", spec);
+                }
+                range
+            }
+        };
+        Ok(range)
+    }
+
+    pub fn days(&self) -> u64 {
+        (self.to - self.from).num_days() as u64 + 1
+    }
+
+    /// The range just before this one: the previous month for a whole
+    /// calendar month, otherwise a range of the same length.
+    pub fn previous(&self) -> Self {
+        let is_month = self.from.day() == 1 && (self.to + Days::new(1)).day() == 1 && self.days() >= 28;
+        if is_month {
+            let from = self.from - Months::new(1);
+            return Self { from, to: self.from - Days::new(1) };
+        }
+        Self { from: self.from - Days::new(self.days()), to: self.from - Days::new(1) }
+    }
+
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        day >= self.from && day <= self.to
+    }
+}
+
+/// Totals for one side of a comparison.
+#[derive(Debug, Clone)]
+pub struct RangeTotals {
+    pub range: DateRange,
+    pub total_minutes: u32,
+    pub activity_count: usize,
+    /// Activities with a commit sha.
+    pub commit_count: usize,
+    pub by_project: Vec<(String, u32)>,
+    pub by_tag: Vec<(String, u32)>,
+}
+
+fn add_to(totals: &mut Vec<(String, u32)>, key: &str, minutes: u32) {
+    match totals.iter_mut().find(|(k, _)| k == key) {
+        Some((_, mins)) => *mins += minutes,
+        None => totals.push((key.to_string(), minutes)),
+    }
+}
+
+impl RangeTotals {
+    pub fn build(activities: &[Activity], range: DateRange, calendar: &Calendar) -> Self {
+        let mut totals = Self {
+            range,
+            total_minutes: 0,
+            activity_count: 0,
+            commit_count: 0,
+            by_project: Vec::new(),
+            by_tag: Vec::new(),
+        };
+        for act in activities {
+            if !calendar.day_of(act).is_some_and(|d| range.contains(d)) {
+                continue;
+            }
+            totals.total_minutes += act.duration_minutes;
+            totals.activity_count += 1;
+            if act.source().is_some_and(|s| s.sha.is_some()) {
+                totals.commit_count += 1;
+            }
+            add_to(&mut totals.by_project, act.project().unwrap_or(NO_PROJECT), act.duration_minutes);
+            for tag in act.tags() {
+                add_to(&mut totals.by_tag, tag, act.duration_minutes);
+            }
+        }
+        totals
+    }
+}
+
+/// Percentage change from `previous` to `current`; `None` when there is
+/// nothing to compare against.
+pub fn percent_change(previous: u32, current: u32) -> Option<f64> {
+    (previous > 0).then(|| (current as f64 - previous as f64) / previous as f64 * 100.0)
+}
+
+/// Two ranges side by side.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub current: RangeTotals,
+    pub previous: RangeTotals,
+}
+
+impl Comparison {
+    pub fn build(activities: &[Activity], current: DateRange, previous: DateRange, calendar: &Calendar) -> Self {
+        Self {
+            current: RangeTotals::build(activities, current, calendar),
+            previous: RangeTotals::build(activities, previous, calendar),
+        }
+    }
+
+    /// `(project, previous, current)` for every project in either range,
+    /// largest current total first.
+    pub fn projects(&self) -> Vec<(String, u32, u32)> {
+        Self::pair(&self.previous.by_project, &self.current.by_project)
+    }
+
+    pub fn tags(&self) -> Vec<(String, u32, u32)> {
+        Self::pair(&self.previous.by_tag, &self.current.by_tag)
+    }
+
+    fn pair(previous: &[(String, u32)], current: &[(String, u32)]) -> Vec<(String, u32, u32)> {
+        let mut rows: Vec<(String, u32, u32)> = current.iter().map(|(k, m)| (k.clone(), 0, *m)).collect();
+        for (key, minutes) in previous {
+            match rows.iter_mut().find(|(k, _, _)| k == key) {
+                Some(row) => row.1 = *minutes,
+                None => rows.push((key.clone(), *minutes, 0)),
+            }
+        }
+        rows.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| b.1.cmp(&a.1)).then_with(|| a.0.cmp(&b.0)));
+        rows
+    }
+}
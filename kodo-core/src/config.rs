@@ -0,0 +1,108 @@
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use anyhow::{Result, Context};
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::goals::Goal;
+use crate::invoice::ProjectConfig;
+use crate::tickets::TicketMatcher;
+use crate::timesheet::Rounding;
+use crate::worklog::JiraConfig;
+
+/// User settings, stored as JSON next to the activities file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Regexes used to find ticket references in activity names and commit
+    /// messages. If a pattern has a capture group, the first group is the
+    /// ticket id; otherwise the whole match is.
+    pub ticket_patterns: Vec<String>,
+    /// Prefixes of matches that aren't tickets, by default standards and
+    /// algorithms written like ticket keys (`UTF-8`, `SHA-256`, `ISO-8601`).
+    pub ticket_exclude: Vec<String>,
+    /// First day of the week for weekly reports, e.g. `monday` or `sun`.
+    pub week_start: String,
+    /// `local`, `UTC` or a fixed offset such as `+05:30`.
+    pub timezone: String,
+    /// Rounding applied to each activity on timesheets.
+    pub rounding: Rounding,
+    /// Per-project settings such as hourly rates.
+    pub projects: BTreeMap<String, ProjectConfig>,
+    /// Time targets and budgets shown by `kodo goals`.
+    pub goals: Vec<Goal>,
+    /// Layout for `kodo standup`; see `Standup::render` for placeholders.
+    pub standup_template: Option<String>,
+    /// Jira site that `kodo jira submit` sends worklogs to.
+    pub jira: JiraConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ticket_patterns: vec![
+                r"\b[A-Z][A-Z0-9]+-\d+\b".to_string(),
+                r"(?:^|[^\w&])(#\d+)\b".to_string(),
+            ],
+            ticket_exclude: ["AES", "CVE", "HTTP", "ISO", "MD", "RFC", "SHA", "TLS", "UTF", "WIN"]
+                .map(str::to_string)
+                .to_vec(),
+            week_start: "monday".to_string(),
+            timezone: "local".to_string(),
+            rounding: Rounding::default(),
+            projects: BTreeMap::new(),
+            goals: Vec::new(),
+            standup_template: None,
+            jira: JiraConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load_from_file(path: &Path) -> Result<Config> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {:?}", path))?;
+        if contents.trim().is_empty() {
+            return Ok(Config::default());
+        }
+
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse JSON in {:?}", path))
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn ticket_matcher(&self) -> Result<TicketMatcher> {
+        TicketMatcher::new(&self.ticket_patterns, &self.ticket_exclude)
+    }
+
+    /// Settings for `project`, matched case-insensitively.
+    pub fn project(&self, project: &str) -> ProjectConfig {
+        self.projects
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(project))
+            .map(|(_, settings)| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether an activity counts towards client totals: its own flag if
+    /// set, else its project's default, else billable.
+    pub fn is_billable(&self, activity: &Activity) -> bool {
+        activity.billable
+            .or_else(|| activity.project().and_then(|p| self.project(p).billable))
+            .unwrap_or(true)
+    }
+
+    pub fn calendar(&self) -> Result<Calendar> {
+        Calendar::new(&self.week_start, &self.timezone)
+    }
+}
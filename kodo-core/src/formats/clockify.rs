@@ -0,0 +1,37 @@
+//! Clockify's detailed report CSV.
+
+use anyhow::Result;
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::config::Config;
+use super::detailed::{self, Layout};
+use super::ImportRow;
+
+const LAYOUT: Layout = Layout {
+    tool: "Clockify",
+    header: &[
+        "Project", "Client", "Description", "Task", "User", "Group", "Email", "Tags", "Billable",
+        "Start Date", "Start Time", "End Date", "End Time", "Duration (h)", "Duration (decimal)",
+    ],
+    description: "Description",
+    project: "Project",
+    tags: "Tags",
+    billable: "Billable",
+    start_date: "Start Date",
+    start_time: "Start Time",
+    end_date: "End Date",
+    end_time: "End Time",
+    duration: "Duration (h)",
+    decimal_duration: Some("Duration (decimal)"),
+    date_format: "%m/%d/%Y",
+    time_format: "%I:%M:%S %p",
+};
+
+pub fn import(text: &str, calendar: &Calendar) -> Result<Vec<ImportRow>> {
+    detailed::import(text, &LAYOUT, calendar)
+}
+
+pub fn export(activities: &[Activity], config: &Config) -> Result<String> {
+    detailed::export(activities, &LAYOUT, config)
+}
@@ -0,0 +1,256 @@
+//! Plain CSV, as produced by spreadsheets or by `kodo export --format csv`.
+
+use anyhow::{Result, bail};
+use std::str::FromStr;
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::config::Config;
+use super::{minutes_between, parse_bool, parse_date, parse_datetime, parse_duration, split_list, ImportRow};
+
+/// Columns written by the exporter, in order. Files with this header import
+/// back without any mapping.
+pub const EXPORT_HEADER: [&str; 10] = ["id", "date", "start", "end", "name", "minutes", "project", "tags", "billable", "tickets"];
+
+/// An activity property a CSV column can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Minutes,
+    /// A duration where plain numbers are hours.
+    Hours,
+    Date,
+    Start,
+    End,
+    Project,
+    Tags,
+    Billable,
+    Tickets,
+}
+
+impl Field {
+    /// Header names recognised for each field, lowercase.
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Field::Name => &["name", "description", "activity", "task", "title"],
+            Field::Minutes => &["minutes", "duration", "duration_minutes", "duration (minutes)", "mins"],
+            Field::Hours => &["hours", "duration (hours)", "time (h)"],
+            Field::Date => &["date", "day", "start date"],
+            Field::Start => &["start", "started_at", "start time", "from"],
+            Field::End => &["end", "ended_at", "end time", "to", "stop"],
+            Field::Project => &["project", "client"],
+            Field::Tags => &["tags", "tag", "labels"],
+            Field::Billable => &["billable"],
+            Field::Tickets => &["tickets", "ticket", "issue", "issues"],
+        }
+    }
+
+    const ALL: [Field; 10] = [
+        Field::Name, Field::Minutes, Field::Hours, Field::Date, Field::Start,
+        Field::End, Field::Project, Field::Tags, Field::Billable, Field::Tickets,
+    ];
+}
+
+impl FromStr for Field {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        Field::ALL
+            .into_iter()
+            .find(|f| f.aliases()[0] == s)
+            .ok_or_else(|| anyhow::anyhow!(
+                "Unknown field {:?} (expected name, minutes, hours, date, start, end, project, tags, billable or tickets)",
+                s,
+            ))
+    }
+}
+
+/// Which column a field is read from: a header name or a 1-based index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    Header(String),
+    Index(usize),
+}
+
+/// A `field=column` mapping, e.g. `name=Description` or `minutes=3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub field: Field,
+    pub column: Column,
+}
+
+impl FromStr for Mapping {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((field, column)) = s.split_once('=') else {
+            bail!("Invalid mapping {:?}, expected FIELD=COLUMN", s);
+        };
+        let column = match column.trim().parse::<usize>() {
+            Ok(0) => bail!("Column numbers start at 1 in {:?}", s),
+            Ok(index) => Column::Index(index),
+            Err(_) => Column::Header(column.trim().to_string()),
+        };
+        Ok(Self { field: field.parse()?, column })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CsvOptions {
+    /// Explicit columns; they take precedence over header names.
+    pub mappings: Vec<Mapping>,
+    /// Whether the first row is a header. `None` detects it.
+    pub has_header: Option<bool>,
+    pub delimiter: Option<u8>,
+}
+
+/// Resolves each field to a 0-based column index.
+fn resolve_columns(header: Option<&[String]>, mappings: &[Mapping]) -> Result<Vec<(Field, usize)>> {
+    let mut columns: Vec<(Field, usize)> = Vec::new();
+    for mapping in mappings {
+        let index = match (&mapping.column, header) {
+            (Column::Index(i), _) => i - 1,
+            (Column::Header(name), Some(header)) => match header.iter().position(|h| h.trim().eq_ignore_ascii_case(name)) {
+                Some(i) => i,
+                None => bail!("Column {:?} not found in the header", name),
+            },
+            (Column::Header(name), None) => bail!("Column {:?} can't be found in a file without a header", name),
+        };
+        columns.push((mapping.field, index));
+    }
+
+    match header {
+        Some(header) => {
+            for (i, name) in header.iter().enumerate() {
+                let name = name.trim().to_ascii_lowercase();
+                if let Some(field) = Field::ALL.into_iter().find(|f| f.aliases().contains(&name.as_str()))
+                    && !columns.iter().any(|(f, c)| *f == field || *c == i)
+                {
+                    columns.push((field, i));
+                }
+            }
+        }
+        // Without a header, fall back to the export layout
+        None if mappings.is_empty() => {
+            for (i, name) in EXPORT_HEADER.iter().enumerate() {
+                if let Ok(field) = name.parse() {
+                    columns.push((field, i));
+                }
+            }
+        }
+        None => {}
+    }
+    Ok(columns)
+}
+
+/// A first row is a header if at least one cell names a known field and
+/// none of its cells parse as a duration or date.
+fn looks_like_header(row: &[String]) -> bool {
+    let known = row.iter().any(|cell| {
+        let cell = cell.trim().to_ascii_lowercase();
+        Field::ALL.iter().any(|f| f.aliases().contains(&cell.as_str()))
+    });
+    let data = row.iter().any(|cell| parse_date(cell).is_ok() || cell.trim().parse::<f64>().is_ok());
+    known && !data
+}
+
+fn read_record(record: &[String], columns: &[(Field, usize)], calendar: &Calendar) -> Result<Activity, String> {
+    let get = |field: Field| {
+        columns
+            .iter()
+            .find(|(f, _)| *f == field)
+            .and_then(|(_, i)| record.get(*i))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    };
+
+    let name = get(Field::Name).ok_or("missing name")?;
+    let mut date = get(Field::Date).map(parse_date).transpose()?;
+    let started_at = get(Field::Start).map(|s| parse_datetime(s, date, calendar)).transpose()?;
+    let ended_at = get(Field::End).map(|s| parse_datetime(s, date, calendar)).transpose()?;
+    if date.is_none() {
+        date = started_at.map(|s| calendar.to_local(s).date());
+    }
+    let date = date.ok_or("missing date")?;
+
+    let duration_minutes = match (get(Field::Minutes), get(Field::Hours), started_at, ended_at) {
+        (Some(minutes), _, _, _) => parse_duration(minutes, false)?,
+        (None, Some(hours), _, _) => parse_duration(hours, true)?,
+        (None, None, Some(start), Some(end)) => minutes_between(start, end)?,
+        _ => return Err("missing duration (and no start and end time)".to_string()),
+    };
+
+    Ok(Activity {
+        name: name.to_string(),
+        duration_minutes,
+        date: date.format("%Y-%m-%d").to_string(),
+        started_at,
+        project: get(Field::Project).map(str::to_string),
+        billable: get(Field::Billable).map(parse_bool).transpose()?,
+        tags: get(Field::Tags).map(split_list).unwrap_or_default(),
+        tickets: get(Field::Tickets).map(split_list).unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+/// Reads CSV text into import rows. Records that can't be read become
+/// rejected rows rather than errors; only an unusable layout fails.
+pub fn import(text: &str, options: &CsvOptions, calendar: &Calendar) -> Result<Vec<ImportRow>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(options.delimiter.unwrap_or(b','))
+        .from_reader(text.as_bytes());
+
+    let mut records: Vec<(usize, Vec<String>)> = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line() as usize).unwrap_or(records.len() + 1);
+        records.push((line, record.iter().map(str::to_string).collect()));
+    }
+    records.retain(|(_, r)| r.iter().any(|cell| !cell.trim().is_empty()));
+
+    let has_header = match (options.has_header, records.first()) {
+        (Some(explicit), _) => explicit,
+        (None, Some((_, first))) => looks_like_header(first),
+        (None, None) => false,
+    };
+    let header = if has_header && !records.is_empty() { Some(records.remove(0).1) } else { None };
+    let columns = resolve_columns(header.as_deref(), &options.mappings)?;
+    if !columns.iter().any(|(f, _)| *f == Field::Name) {
+        bail!("No name column found; map one with --map name=COLUMN");
+    }
+
+    Ok(records
+        .into_iter()
+        .map(|(line, record)| ImportRow { file: None, line, result: read_record(&record, &columns, calendar) })
+        .collect())
+}
+
+/// Writes activities with [`EXPORT_HEADER`] columns.
+pub fn export(activities: &[Activity], config: &Config) -> Result<String> {
+    let calendar = config.calendar()?;
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(EXPORT_HEADER)?;
+    for act in activities {
+        let date = calendar.day_of(act).map(|d| d.to_string()).unwrap_or_else(|| act.date.clone());
+        let start = act.started_at.map(|s| s.to_rfc3339()).unwrap_or_default();
+        let end = act.started_at
+            .map(|s| (s + chrono::Duration::minutes(act.duration_minutes as i64)).to_rfc3339())
+            .unwrap_or_default();
+        writer.write_record([
+            act.id.to_string(),
+            date,
+            start,
+            end,
+            act.name.clone(),
+            act.duration_minutes.to_string(),
+            act.project.clone().unwrap_or_default(),
+            act.tags.join(", "),
+            if config.is_billable(act) { "yes" } else { "no" }.to_string(),
+            act.tickets.join(", "),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
@@ -0,0 +1,123 @@
+//! Detailed time reports as exported by Toggl and Clockify: one CSV row per
+//! time entry with separate start/end date and time columns.
+
+use chrono::Duration;
+use anyhow::{Result, bail};
+
+use crate::activity::Activity;
+use crate::config::Config;
+use crate::calendar::Calendar;
+use super::{minutes_between, parse_bool, parse_date, parse_datetime, parse_duration, scheduled, split_list, ImportRow};
+
+/// Column names and value formats of one tool's report.
+pub(crate) struct Layout {
+    pub tool: &'static str,
+    /// Columns written on export, in order.
+    pub header: &'static [&'static str],
+    pub description: &'static str,
+    pub project: &'static str,
+    pub tags: &'static str,
+    pub billable: &'static str,
+    pub start_date: &'static str,
+    pub start_time: &'static str,
+    pub end_date: &'static str,
+    pub end_time: &'static str,
+    /// Duration as `HH:MM:SS`.
+    pub duration: &'static str,
+    /// Duration in decimal hours, if the tool writes one.
+    pub decimal_duration: Option<&'static str>,
+    pub date_format: &'static str,
+    pub time_format: &'static str,
+}
+
+fn hms(minutes: u32) -> String {
+    format!("{:02}:{:02}:00", minutes / 60, minutes % 60)
+}
+
+pub(crate) fn import(text: &str, layout: &Layout, calendar: &Calendar) -> Result<Vec<ImportRow>> {
+    let mut reader = ::csv::ReaderBuilder::new().flexible(true).from_reader(text.trim_start_matches('\u{feff}').as_bytes());
+    let header: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    let column = |name: &str| header.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let required = |name: &str| match column(name) {
+        Some(i) => Ok(i),
+        None => bail!("Not a {} detailed report: missing column {:?}", layout.tool, name),
+    };
+
+    let description = required(layout.description)?;
+    let start_date = required(layout.start_date)?;
+    let start_time = required(layout.start_time)?;
+    let end_date = column(layout.end_date);
+    let end_time = column(layout.end_time);
+    let duration = column(layout.duration);
+    let project = column(layout.project);
+    let tags = column(layout.tags);
+    let billable = column(layout.billable);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line() as usize).unwrap_or(rows.len() + 2);
+        let get = |i: Option<usize>| i.and_then(|i| record.get(i)).map(str::trim).filter(|v| !v.is_empty());
+
+        let result = (|| -> Result<Activity, String> {
+            let day = parse_date(get(Some(start_date)).ok_or("missing start date")?)?;
+            let started_at = parse_datetime(get(Some(start_time)).ok_or("missing start time")?, Some(day), calendar)?;
+            let ended_at = match (get(end_date), get(end_time)) {
+                (end_day, Some(time)) => {
+                    let end_day = end_day.map(parse_date).transpose()?.unwrap_or(day);
+                    Some(parse_datetime(time, Some(end_day), calendar)?)
+                }
+                _ => None,
+            };
+            let duration_minutes = match (get(duration), ended_at) {
+                (Some(d), _) => parse_duration(d, false)?,
+                (None, Some(end)) => minutes_between(started_at, end)?,
+                (None, None) => return Err("missing duration and end time".to_string()),
+            };
+
+            Ok(Activity {
+                name: get(Some(description)).unwrap_or("(no description)").to_string(),
+                duration_minutes,
+                date: day.format("%Y-%m-%d").to_string(),
+                started_at: Some(started_at),
+                project: get(project).map(str::to_string),
+                billable: get(billable).map(parse_bool).transpose()?,
+                tags: get(tags).map(split_list).unwrap_or_default(),
+                ..Default::default()
+            })
+        })();
+        rows.push(ImportRow { file: None, line, result });
+    }
+    Ok(rows)
+}
+
+/// Writes one row per activity. Activities without a start time follow one
+/// another from 09:00, as in the other calendar-like exports.
+pub(crate) fn export(activities: &[Activity], layout: &Layout, config: &Config) -> Result<String> {
+    let calendar = config.calendar()?;
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(layout.header)?;
+    for (act, started) in scheduled(activities, &calendar) {
+        let start = calendar.to_local(started);
+        let end = start + Duration::minutes(act.duration_minutes as i64);
+
+        let record: Vec<String> = layout.header
+            .iter()
+            .map(|&column| match column {
+                c if c == layout.description => act.name.lines().next().unwrap_or("").to_string(),
+                c if c == layout.project => act.project.clone().unwrap_or_default(),
+                c if c == layout.tags => act.tags.join(", "),
+                c if c == layout.billable => if config.is_billable(act) { "Yes" } else { "No" }.to_string(),
+                c if c == layout.start_date => start.format(layout.date_format).to_string(),
+                c if c == layout.start_time => start.format(layout.time_format).to_string(),
+                c if c == layout.end_date => end.format(layout.date_format).to_string(),
+                c if c == layout.end_time => end.format(layout.time_format).to_string(),
+                c if c == layout.duration => hms(act.duration_minutes),
+                c if Some(c) == layout.decimal_duration => format!("{:.2}", act.duration_minutes as f64 / 60.0),
+                _ => String::new(),
+            })
+            .collect();
+        writer.write_record(&record)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
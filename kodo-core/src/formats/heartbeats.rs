@@ -0,0 +1,153 @@
+//! Heartbeat streams from editor plugins and ActivityWatch.
+//!
+//! A heartbeat says "this entity was being worked on at this time", with an
+//! optional project and duration. Heartbeats of the same project that are no
+//! more than the timeout apart form a session, and each session becomes one
+//! activity. Accepted layouts:
+//!
+//! - a plain list of heartbeats: `[{"timestamp": ..., "entity": ..., "project": ..., "duration": ...}]`
+//! - a WakaTime data dump: `{"days": [{"heartbeats": [...]}]}`
+//! - an ActivityWatch export: `{"buckets": {"...": {"events": [...]}}}`, where
+//!   the entity and project are read from each event's `data`
+//!
+//! Timestamps are RFC 3339 strings or Unix seconds; durations are seconds.
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, FixedOffset, Local};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::sessions::group_sessions;
+use super::ImportRow;
+
+/// Fields that can name what a heartbeat was about, in order of preference.
+const ENTITY_FIELDS: [&str; 5] = ["entity", "file", "app", "title", "url"];
+
+#[derive(Debug, Clone)]
+struct Heartbeat {
+    /// Position in the file, starting at 1.
+    record: usize,
+    time: DateTime<FixedOffset>,
+    duration: Duration,
+    entity: Option<String>,
+    project: Option<String>,
+}
+
+fn array(value: Option<&Value>) -> Option<Vec<&Value>> {
+    value.and_then(Value::as_array).map(|a| a.iter().collect())
+}
+
+/// The heartbeat records in a parsed export, wherever the layout keeps them.
+fn records(root: &Value) -> Option<Vec<&Value>> {
+    match root {
+        Value::Array(items) => Some(items.iter().collect()),
+        Value::Object(map) => array(map.get("heartbeats"))
+            .or_else(|| array(map.get("events")))
+            .or_else(|| {
+                let days = map.get("days")?.as_array()?;
+                Some(days.iter().flat_map(|d| array(d.get("heartbeats")).unwrap_or_default()).collect())
+            })
+            .or_else(|| {
+                let buckets = map.get("buckets")?.as_object()?;
+                Some(buckets.values().flat_map(|b| array(b.get("events")).unwrap_or_default()).collect())
+            }),
+        _ => None,
+    }
+}
+
+fn from_unix(seconds: f64) -> Option<DateTime<FixedOffset>> {
+    // Some tools write milliseconds
+    let seconds = if seconds > 1e11 { seconds / 1000.0 } else { seconds };
+    let nanos = (seconds.fract() * 1e9).round() as u32;
+    DateTime::from_timestamp(seconds.trunc() as i64, nanos.min(999_999_999)).map(|t| t.fixed_offset())
+}
+
+fn read_heartbeat(record: usize, value: &Value) -> Result<Heartbeat, String> {
+    let field = |name: &str| value.get(name).or_else(|| value.get("data")?.get(name));
+    let text = |name: &str| field(name).and_then(Value::as_str).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+
+    let time = match field("timestamp").or_else(|| field("time")) {
+        Some(Value::Number(n)) => n.as_f64().and_then(from_unix),
+        Some(Value::String(s)) => DateTime::parse_from_rfc3339(s).ok().or_else(|| s.parse().ok().and_then(from_unix)),
+        Some(_) => None,
+        None => return Err("missing timestamp".to_string()),
+    }
+    .ok_or("invalid timestamp")?;
+
+    let seconds = match field("duration") {
+        Some(d) => d.as_f64().filter(|s| *s >= 0.0).ok_or("invalid duration")?,
+        None => 0.0,
+    };
+
+    Ok(Heartbeat {
+        record,
+        time,
+        duration: Duration::milliseconds((seconds * 1000.0).round() as i64),
+        entity: ENTITY_FIELDS.iter().find_map(|f| text(f)),
+        project: text("project"),
+    })
+}
+
+/// The last path component of each entity, most recent first, e.g.
+/// `main.rs, lib.rs (+2 more)`.
+fn summarize(entities: &[Option<String>]) -> String {
+    let mut names: Vec<&str> = Vec::new();
+    for entity in entities.iter().rev().flatten() {
+        let name = entity.rsplit(['/', '\\']).find(|s| !s.is_empty()).unwrap_or(entity);
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    match names.len() {
+        0 => "(no entity)".to_string(),
+        1..=3 => names.join(", "),
+        n => format!("{} (+{} more)", names[..3].join(", "), n - 3),
+    }
+}
+
+/// Reads heartbeats and groups them into one activity per session. A
+/// session ends when the next heartbeat of the same project starts more
+/// than `timeout` after the previous one ended. Heartbeats that can't be
+/// read are reported as rejected rows.
+pub fn import(text: &str, timeout: Duration, calendar: &Calendar) -> Result<Vec<ImportRow>> {
+    let root: Value = serde_json::from_str(text)?;
+    let Some(records) = records(&root) else {
+        bail!("No heartbeats found; expected a list of heartbeats, a WakaTime data dump or an ActivityWatch export");
+    };
+
+    let mut rows = Vec::new();
+    let mut by_project: BTreeMap<Option<String>, Vec<Heartbeat>> = BTreeMap::new();
+    for (i, value) in records.into_iter().enumerate() {
+        match read_heartbeat(i + 1, value) {
+            Ok(beat) => by_project.entry(beat.project.clone()).or_default().push(beat),
+            Err(reason) => rows.push(ImportRow { file: None, line: i + 1, result: Err(reason) }),
+        }
+    }
+
+    for (project, beats) in by_project {
+        let events = beats
+            .into_iter()
+            .map(|b| (b.time.with_timezone(&Local), (b.time + b.duration).with_timezone(&Local), b))
+            .collect();
+        for session in group_sessions(events, timeout) {
+            let start = session.items[0].time;
+            let entities: Vec<Option<String>> = session.items.iter().map(|b| b.entity.clone()).collect();
+            rows.push(ImportRow {
+                file: None,
+                line: session.items.iter().map(|b| b.record).min().unwrap_or(0),
+                result: Ok(Activity {
+                    name: summarize(&entities),
+                    duration_minutes: session.minutes(),
+                    date: calendar.to_local(start).date().format("%Y-%m-%d").to_string(),
+                    started_at: Some(start),
+                    project: project.clone(),
+                    ..Default::default()
+                }),
+            });
+        }
+    }
+    rows.sort_by_key(|r| r.line);
+    Ok(rows)
+}
@@ -0,0 +1,286 @@
+//! iCalendar (RFC 5545) files, for showing logged work in calendar apps and
+//! for turning calendar events such as meetings into activities.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, Utc};
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use super::{minutes_between, scheduled, ImportRow};
+
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Splits content lines longer than 75 octets, as the spec requires.
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Writes one VEVENT per activity. Activities without a start time are laid
+/// out one after another from 09:00 on their day.
+pub fn export(activities: &[Activity], calendar: &Calendar, now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//kodo//kodo activity tracker//EN", "CALSCALE:GREGORIAN"] {
+        out.push_str(&fold(line));
+    }
+
+    for (act, start) in scheduled(activities, calendar) {
+        let start = start.with_timezone(&Utc);
+        let end = start + Duration::minutes(act.duration_minutes as i64);
+
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:kodo-{}-{}@kodo", act.id, start.format(TIME_FORMAT)),
+            format!("DTSTAMP:{}", now.format(TIME_FORMAT)),
+            format!("DTSTART:{}", start.format(TIME_FORMAT)),
+            format!("DTEND:{}", end.format(TIME_FORMAT)),
+            format!("SUMMARY:{}", escape(act.name.lines().next().unwrap_or(""))),
+        ];
+        if act.name.lines().count() > 1 {
+            lines.push(format!("DESCRIPTION:{}", escape(&act.name)));
+        }
+        if !act.tags.is_empty() {
+            lines.push(format!("CATEGORIES:{}", act.tags.iter().map(|t| escape(t)).collect::<Vec<_>>().join(",")));
+        }
+        if let Some(project) = &act.project {
+            lines.push(format!("X-KODO-PROJECT:{}", escape(project)));
+        }
+        lines.push("END:VEVENT".to_string());
+        for line in lines {
+            out.push_str(&fold(&line));
+        }
+    }
+    out.push_str(&fold("END:VCALENDAR"));
+    out
+}
+
+/// Which events to import. An event matches if it has any of the keywords
+/// in its summary or description, or any of the categories. With neither
+/// set, every event matches.
+#[derive(Debug, Clone, Default)]
+pub struct IcsFilter {
+    pub keywords: Vec<String>,
+    pub categories: Vec<String>,
+}
+
+/// One property line: name, parameters and value.
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // The value starts after the first colon outside a quoted parameter.
+    let mut quoted = false;
+    let split = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ':' && !quoted
+    })?.0;
+    let (head, value) = (&line[..split], &line[split + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some(Property { name, params, value: value.to_string() })
+}
+
+/// Parses an ISO 8601 duration such as `PT1H30M` or `P1DT2H`.
+fn parse_ics_duration(s: &str) -> Result<u32, String> {
+    let invalid = || format!("invalid duration {:?}", s);
+    let body = s.trim().strip_prefix('P').ok_or_else(invalid)?;
+    let mut minutes = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in body.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let value: f64 = number.parse().map_err(|_| invalid())?;
+                number.clear();
+                minutes += match (unit, in_time) {
+                    ('W', false) => value * 7.0 * 24.0 * 60.0,
+                    ('D', false) => value * 24.0 * 60.0,
+                    ('H', true) => value * 60.0,
+                    ('M', true) => value,
+                    ('S', true) => value / 60.0,
+                    _ => return Err(invalid()),
+                };
+            }
+        }
+    }
+    Ok(minutes.round() as u32)
+}
+
+/// A DTSTART/DTEND value. Times with a TZID are taken in the calendar's
+/// timezone, as are floating times. `None` for all-day dates.
+fn parse_ics_time(prop: &Property, calendar: &Calendar) -> Result<Option<DateTime<FixedOffset>>, String> {
+    let value = prop.value.trim();
+    let is_date = prop.params.iter().any(|(k, v)| k == "VALUE" && v.eq_ignore_ascii_case("DATE"));
+    if is_date || (value.len() == 8 && !value.contains('T')) {
+        return Ok(None);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map_err(|_| format!("invalid time {:?}", value))?;
+        return Ok(Some(naive.and_utc().fixed_offset()));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map_err(|_| format!("invalid time {:?}", value))?;
+    calendar.localize(naive).map(Some).ok_or_else(|| format!("time {:?} does not exist in this timezone", value))
+}
+
+fn split_categories(value: &str) -> Vec<String> {
+    // Commas separate categories unless escaped
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                current.extend(chars.next());
+            }
+            ',' => out.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    out.push(current);
+    out.iter().map(|c| unescape(c).trim().to_string()).filter(|c| !c.is_empty()).collect()
+}
+
+fn read_event(props: &[Property], calendar: &Calendar) -> Result<Activity, String> {
+    let get = |name: &str| props.iter().find(|p| p.name == name);
+
+    let summary = get("SUMMARY").map(|p| unescape(&p.value)).filter(|s| !s.trim().is_empty());
+    let name = summary.ok_or("event has no summary")?;
+    if get("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED")) {
+        return Err("event was cancelled".to_string());
+    }
+    let start = match get("DTSTART") {
+        Some(prop) => parse_ics_time(prop, calendar)?.ok_or("all-day event")?,
+        None => return Err("event has no start time".to_string()),
+    };
+    let duration_minutes = match (get("DTEND"), get("DURATION")) {
+        (Some(end), _) => minutes_between(start, parse_ics_time(end, calendar)?.ok_or("all-day event")?)?,
+        (None, Some(duration)) => parse_ics_duration(&duration.value)?,
+        (None, None) => 0,
+    };
+    if duration_minutes == 0 {
+        return Err("event has no duration".to_string());
+    }
+
+    Ok(Activity {
+        name,
+        duration_minutes,
+        date: calendar.to_local(start).date().format("%Y-%m-%d").to_string(),
+        started_at: Some(start),
+        project: get("X-KODO-PROJECT").map(|p| unescape(&p.value)),
+        tags: props
+            .iter()
+            .filter(|p| p.name == "CATEGORIES")
+            .flat_map(|p| split_categories(&p.value))
+            .collect(),
+        ..Default::default()
+    })
+}
+
+fn matches(props: &[Property], filter: &IcsFilter) -> bool {
+    if filter.keywords.is_empty() && filter.categories.is_empty() {
+        return true;
+    }
+    let text: String = props
+        .iter()
+        .filter(|p| p.name == "SUMMARY" || p.name == "DESCRIPTION")
+        .map(|p| unescape(&p.value).to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let categories: Vec<String> = props
+        .iter()
+        .filter(|p| p.name == "CATEGORIES")
+        .flat_map(|p| split_categories(&p.value))
+        .collect();
+    filter.keywords.iter().any(|k| text.contains(&k.to_lowercase()))
+        || filter.categories.iter().any(|c| categories.iter().any(|ec| ec.eq_ignore_ascii_case(c)))
+}
+
+/// Reads the events in an `.ics` file that pass `filter`. Recurring events
+/// are imported once, at their first occurrence.
+pub fn import(text: &str, filter: &IcsFilter, calendar: &Calendar) -> Vec<ImportRow> {
+    // Unfold continuation lines, remembering where each logical line began
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((i + 1, raw.to_string())),
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut event: Option<(usize, Vec<Property>)> = None;
+    let mut depth = 0;
+    for (line, text) in lines {
+        let upper = text.to_ascii_uppercase();
+        if upper == "BEGIN:VEVENT" {
+            event = Some((line, Vec::new()));
+            depth = 0;
+        } else if upper == "END:VEVENT" {
+            if let Some((start_line, props)) = event.take()
+                && matches(&props, filter)
+            {
+                rows.push(ImportRow { file: None, line: start_line, result: read_event(&props, calendar) });
+            }
+        } else if let Some((_, props)) = event.as_mut() {
+            // Skip nested components such as VALARM
+            if upper.starts_with("BEGIN:") {
+                depth += 1;
+            } else if upper.starts_with("END:") {
+                depth -= 1;
+            } else if depth == 0
+                && let Some(prop) = parse_property(&text)
+            {
+                props.push(prop);
+            }
+        }
+    }
+    rows
+}
@@ -0,0 +1,69 @@
+//! Markdown daily notes: a section per day listing its activities, followed
+//! by the day's total.
+//!
+//! ```text
+//! ## 2026-10-15 Thursday
+//!
+//! - 09:00-10:30 Fix the parser (1:30) *acme* #code
+//! - Review pull requests (0:30)
+//!
+//! **Total: 2:00**
+//! ```
+
+use chrono::{Duration, NaiveDate};
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::timesheet::format_hours;
+
+fn item(act: &Activity, calendar: &Calendar) -> String {
+    let mut line = String::from("- ");
+    if let Some(started) = act.started_at {
+        let start = calendar.to_local(started);
+        let end = start + Duration::minutes(act.duration_minutes as i64);
+        line.push_str(&format!("{}-{} ", start.format("%H:%M"), end.format("%H:%M")));
+    }
+    line.push_str(&format!("{} ({})", act.name.lines().next().unwrap_or(""), format_hours(act.duration_minutes)));
+    if let Some(project) = &act.project {
+        line.push_str(&format!(" *{}*", project));
+    }
+    for tag in &act.tags {
+        line.push_str(&format!(" #{}", tag.replace(char::is_whitespace, "-")));
+    }
+    // Further lines of the name become an indented note
+    for note in act.name.lines().skip(1).filter(|l| !l.trim().is_empty()) {
+        line.push_str(&format!("\n  {}", note.trim()));
+    }
+    line
+}
+
+/// Writes a `## YYYY-MM-DD Weekday` section per day, oldest first. Timed
+/// activities are listed by start time, untimed ones after them.
+pub fn export(activities: &[Activity], calendar: &Calendar) -> String {
+    let mut entries: Vec<(NaiveDate, &Activity)> = activities
+        .iter()
+        .filter_map(|act| Some((calendar.day_of(act)?, act)))
+        .collect();
+    // Stable, so untimed activities keep their order
+    entries.sort_by_key(|(day, act)| (*day, act.started_at.is_none(), act.started_at));
+
+    let mut out = String::new();
+    let mut index = 0;
+    while index < entries.len() {
+        let day = entries[index].0;
+        let items: Vec<&Activity> = entries[index..].iter().take_while(|(d, _)| *d == day).map(|(_, a)| *a).collect();
+        index += items.len();
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("## {}\n\n", day.format("%Y-%m-%d %A")));
+        for act in &items {
+            out.push_str(&item(act, calendar));
+            out.push('\n');
+        }
+        let total: u32 = items.iter().map(|a| a.duration_minutes).sum();
+        out.push_str(&format!("\n**Total: {}**\n", format_hours(total)));
+    }
+    out
+}
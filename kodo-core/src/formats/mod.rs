@@ -0,0 +1,274 @@
+//! Reading and writing activities in other tools' formats.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::HashMap;
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+
+pub mod clockify;
+pub mod csv;
+mod detailed;
+pub mod heartbeats;
+pub mod ical;
+pub mod markdown;
+pub mod org;
+pub mod timewarrior;
+pub mod toggl;
+
+/// One record read from an import file, before it is checked against the
+/// existing activities.
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    /// Source file, when several files are imported at once.
+    pub file: Option<String>,
+    /// Line (or record) number in the source file, starting at 1.
+    pub line: usize,
+    /// The activity, or why the record couldn't be read.
+    pub result: Result<Activity, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportStatus {
+    Added,
+    /// Skipped because an equivalent activity exists; holds the reason.
+    Duplicate(String),
+    /// Not imported; holds the reason.
+    Rejected(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportEntry {
+    pub file: Option<String>,
+    pub line: usize,
+    pub activity: Option<Activity>,
+    pub status: ImportStatus,
+}
+
+impl ImportEntry {
+    /// `line 4`, or `2026-10.data:4` for multi-file imports.
+    pub fn location(&self) -> String {
+        match &self.file {
+            Some(file) => format!("{}:{}", file, self.line),
+            None => format!("line {}", self.line),
+        }
+    }
+}
+
+/// What an import did, or would do in a dry run.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub entries: Vec<ImportEntry>,
+}
+
+impl ImportReport {
+    /// Checks parsed rows against `existing` and earlier rows in the same
+    /// file. New activities get ids following the highest existing one.
+    pub fn build(existing: &[Activity], rows: Vec<ImportRow>, calendar: &Calendar) -> Self {
+        let mut next_id = existing.iter().map(|a| a.id).max().unwrap_or(0) + 1;
+        let mut entries: Vec<ImportEntry> = Vec::new();
+        for row in rows {
+            let mut act = match row.result {
+                Ok(act) => act,
+                Err(reason) => {
+                    entries.push(ImportEntry {
+                        file: row.file,
+                        line: row.line,
+                        activity: None,
+                        status: ImportStatus::Rejected(reason),
+                    });
+                    continue;
+                }
+            };
+
+            let status = if let Some(other) = existing.iter().find(|e| same_activity(e, &act, calendar)) {
+                ImportStatus::Duplicate(format!("matches existing activity #{}", other.id))
+            } else if let Some(earlier) = entries.iter().find(|e| {
+                e.status == ImportStatus::Added && e.activity.as_ref().is_some_and(|a| same_activity(a, &act, calendar))
+            }) {
+                ImportStatus::Duplicate(format!("repeats {}", earlier.location()))
+            } else {
+                act.id = next_id;
+                next_id += 1;
+                ImportStatus::Added
+            };
+            entries.push(ImportEntry { file: row.file, line: row.line, activity: Some(act), status });
+        }
+        Self { entries }
+    }
+
+    pub fn added(&self) -> impl Iterator<Item = &Activity> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == ImportStatus::Added)
+            .filter_map(|e| e.activity.as_ref())
+    }
+
+    /// `(added, duplicates, rejected)`.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.entries.iter().fold((0, 0, 0), |(a, d, r), e| match e.status {
+            ImportStatus::Added => (a + 1, d, r),
+            ImportStatus::Duplicate(_) => (a, d + 1, r),
+            ImportStatus::Rejected(_) => (a, d, r + 1),
+        })
+    }
+}
+
+/// Two activities are the same if they share day, duration and first line
+/// of the name, and their start times (when both have one) agree.
+fn same_activity(a: &Activity, b: &Activity, calendar: &Calendar) -> bool {
+    let title = |act: &Activity| act.name.lines().next().unwrap_or("").trim().to_lowercase();
+    a.duration_minutes == b.duration_minutes
+        && calendar.day_of(a) == calendar.day_of(b)
+        && title(a) == title(b)
+        && (a.started_at.is_none() || b.started_at.is_none() || a.started_at == b.started_at)
+}
+
+/// Rounds fractional minutes, rejecting amounts that don't fit a `u32`.
+fn whole_minutes(minutes: f64) -> Result<u32, String> {
+    let minutes = minutes.round();
+    if minutes > u32::MAX as f64 {
+        return Err("duration too large".to_string());
+    }
+    Ok(minutes as u32)
+}
+
+/// Parses a duration such as `90`, `1:30`, `1:30:00`, `1h30m`, `45m` or
+/// `1.5h`. Plain numbers are minutes, or hours if `hours` is set.
+pub fn parse_duration(s: &str, hours: bool) -> Result<u32, String> {
+    let s = s.trim().to_ascii_lowercase();
+    let invalid = || format!("invalid duration {:?}", s);
+    if s.is_empty() {
+        return Err("empty duration".to_string());
+    }
+    if s.contains(':') {
+        let parts: Vec<u32> = s.split(':').map(|p| p.parse().map_err(|_| invalid())).collect::<Result<_, _>>()?;
+        let (h, m, extra) = match parts.as_slice() {
+            [h, m] => (*h, *m, 0),
+            [h, m, sec] => (*h, *m, u32::from(*sec >= 30)),
+            _ => return Err(invalid()),
+        };
+        return h
+            .checked_mul(60)
+            .and_then(|total| total.checked_add(m))
+            .and_then(|total| total.checked_add(extra))
+            .ok_or_else(|| "duration too large".to_string());
+    }
+    if let Ok(value) = s.parse::<f64>() {
+        let minutes = if hours { value * 60.0 } else { value };
+        if minutes.is_nan() || minutes < 0.0 {
+            return Err(invalid());
+        }
+        return whole_minutes(minutes);
+    }
+
+    // Unit suffixes, e.g. `1h 30m`, `2.5h`, `45min`, `30s`
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut unit = String::new();
+    let mut flush = |number: &mut String, unit: &mut String| -> Result<(), String> {
+        if number.is_empty() {
+            return if unit.is_empty() { Ok(()) } else { Err(invalid()) };
+        }
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        total += match unit.as_str() {
+            "h" | "hr" | "hrs" | "hour" | "hours" => value * 60.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => value,
+            "s" | "sec" | "secs" | "second" | "seconds" => value / 60.0,
+            _ => return Err(invalid()),
+        };
+        number.clear();
+        unit.clear();
+        Ok(())
+    };
+    for c in s.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            if !unit.is_empty() {
+                flush(&mut number, &mut unit)?;
+            }
+            number.push(c);
+        } else if c.is_alphabetic() {
+            unit.push(c);
+        } else if !c.is_whitespace() {
+            return Err(invalid());
+        }
+    }
+    flush(&mut number, &mut unit)?;
+    whole_minutes(total)
+}
+
+/// Parses `YYYY-MM-DD`, `YYYY/MM/DD`, `DD.MM.YYYY` or `MM/DD/YYYY`.
+pub fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    let s = s.trim();
+    ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y", "%m/%d/%Y"]
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(s, f).ok())
+        .ok_or_else(|| format!("invalid date {:?}", s))
+}
+
+/// Parses a timestamp with an offset (RFC 3339), a date and time without
+/// one (taken in the calendar's timezone), or a bare time on `day`.
+pub fn parse_datetime(s: &str, day: Option<NaiveDate>, calendar: &Calendar) -> Result<DateTime<FixedOffset>, String> {
+    let s = s.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time);
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .or_else(|| {
+            let time = ["%H:%M:%S", "%H:%M", "%I:%M %p", "%I:%M:%S %p"]
+                .iter()
+                .find_map(|f| NaiveTime::parse_from_str(s, f).ok())?;
+            Some(day?.and_time(time))
+        })
+        .ok_or_else(|| format!("invalid time {:?}", s))?;
+    calendar.localize(naive).ok_or_else(|| format!("time {:?} does not exist in this timezone", s))
+}
+
+/// Whole minutes from `start` to `end`, rounded to the nearest minute.
+pub(crate) fn minutes_between(start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> Result<u32, String> {
+    let seconds = (end - start).num_seconds();
+    if seconds < 0 {
+        return Err("ends before it starts".to_string());
+    }
+    Ok(((seconds + 30) / 60) as u32)
+}
+
+/// Start times for formats that need one. Activities without a start time
+/// follow one another from 09:00 on their day; those whose day can't be
+/// read are left out.
+pub(crate) fn scheduled<'a>(activities: &'a [Activity], calendar: &Calendar) -> Vec<(&'a Activity, DateTime<FixedOffset>)> {
+    let mut next_free: HashMap<NaiveDate, NaiveDateTime> = HashMap::new();
+    activities
+        .iter()
+        .filter_map(|act| {
+            if let Some(started) = act.started_at {
+                return Some((act, started));
+            }
+            let day = calendar.day_of(act)?;
+            let slot = next_free.entry(day).or_insert_with(|| day.and_hms_opt(9, 0, 0).unwrap());
+            let start = calendar.localize(*slot)?;
+            *slot += Duration::minutes(act.duration_minutes as i64);
+            Some((act, start))
+        })
+        .collect()
+}
+
+/// Parses `yes`/`no`, `true`/`false`, `y`/`n` and `1`/`0`.
+pub fn parse_bool(s: &str) -> Result<bool, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" => Ok(true),
+        "no" | "n" | "false" | "0" => Ok(false),
+        other => Err(format!("invalid yes/no value {:?}", other)),
+    }
+}
+
+/// Splits a list of tags separated by commas, semicolons or pipes.
+pub fn split_list(s: &str) -> Vec<String> {
+    s.split([',', ';', '|'])
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,215 @@
+//! Org-mode journals: one heading per day, one subheading per activity with
+//! its time in a `CLOCK:` line.
+//!
+//! ```text
+//! * [2026-10-15 Thu] (2:00)
+//! ** Fix the parser                                      :code:
+//! :PROPERTIES:
+//! :PROJECT:  acme
+//! :END:
+//! :LOGBOOK:
+//! CLOCK: [2026-10-15 Thu 09:00]--[2026-10-15 Thu 10:30] =>  1:30
+//! :END:
+//! ```
+//!
+//! Imports read `CLOCK:` lines anywhere in a file; each becomes an activity
+//! named after the heading it is filed under.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime};
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::timesheet::format_hours;
+use super::{minutes_between, parse_bool, scheduled, ImportRow};
+
+const STAMP_FORMAT: &str = "%Y-%m-%d %a %H:%M";
+/// Keywords org puts in front of a heading's title.
+const KEYWORDS: [&str; 8] = ["TODO", "NEXT", "STARTED", "WAITING", "HOLD", "DONE", "CANCELLED", "CANCELED"];
+
+/// Org tags may only hold letters, digits, `_`, `@`, `#` and `%`.
+fn org_tag(tag: &str) -> String {
+    tag.chars()
+        .map(|c| if c.is_alphanumeric() || "_@#%".contains(c) { c } else { '_' })
+        .collect()
+}
+
+fn clock_line(start: NaiveDateTime, minutes: u32) -> String {
+    let end = start + Duration::minutes(minutes as i64);
+    format!(
+        "CLOCK: [{}]--[{}] => {:>5}",
+        start.format(STAMP_FORMAT),
+        end.format(STAMP_FORMAT),
+        format_hours(minutes),
+    )
+}
+
+/// Writes a heading per day, with the day's total, and a clocked
+/// subheading per activity. Activities without a start time follow one
+/// another from 09:00.
+pub fn export(activities: &[Activity], calendar: &Calendar) -> String {
+    let mut entries: Vec<(NaiveDateTime, &Activity)> = scheduled(activities, calendar)
+        .into_iter()
+        .map(|(act, start)| (calendar.to_local(start), act))
+        .collect();
+    entries.sort_by_key(|(start, _)| *start);
+
+    let mut out = String::new();
+    let mut days: Vec<(NaiveDate, Vec<(NaiveDateTime, &Activity)>)> = Vec::new();
+    for (start, act) in entries {
+        match days.last_mut() {
+            Some((day, items)) if *day == start.date() => items.push((start, act)),
+            _ => days.push((start.date(), vec![(start, act)])),
+        }
+    }
+
+    for (day, items) in days {
+        let total: u32 = items.iter().map(|(_, a)| a.duration_minutes).sum();
+        out.push_str(&format!("* [{}] ({})\n", day.format("%Y-%m-%d %a"), format_hours(total)));
+        for (start, act) in items {
+            let mut lines = act.name.lines();
+            let mut heading = format!("** {}", lines.next().unwrap_or(""));
+            if !act.tags.is_empty() {
+                let tags: Vec<String> = act.tags.iter().map(|t| org_tag(t)).collect();
+                heading.push_str(&format!(" :{}:", tags.join(":")));
+            }
+            out.push_str(&heading);
+            out.push('\n');
+
+            if act.project.is_some() || act.billable.is_some() {
+                out.push_str(":PROPERTIES:\n");
+                if let Some(project) = &act.project {
+                    out.push_str(&format!(":PROJECT:  {}\n", project));
+                }
+                if let Some(billable) = act.billable {
+                    out.push_str(&format!(":BILLABLE: {}\n", if billable { "yes" } else { "no" }));
+                }
+                out.push_str(":END:\n");
+            }
+            out.push_str(":LOGBOOK:\n");
+            out.push_str(&clock_line(start, act.duration_minutes));
+            out.push_str("\n:END:\n");
+            for line in lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// A heading and what it says about the activities clocked under it.
+#[derive(Debug, Default)]
+struct Heading {
+    level: usize,
+    title: String,
+    tags: Vec<String>,
+    project: Option<String>,
+    billable: Option<bool>,
+}
+
+fn parse_heading(line: &str) -> Option<Heading> {
+    let level = line.chars().take_while(|&c| c == '*').count();
+    let rest = line[level..].strip_prefix(' ')?;
+    if level == 0 {
+        return None;
+    }
+
+    let mut title = rest.trim();
+    let mut tags = Vec::new();
+    // Trailing `:tag1:tag2:`
+    if let Some((head, last)) = title.rsplit_once(char::is_whitespace)
+        && last.len() > 2
+        && last.starts_with(':')
+        && last.ends_with(':')
+    {
+        tags = last.trim_matches(':').split(':').map(str::to_string).collect();
+        title = head.trim_end();
+    }
+    if let Some((first, after)) = title.split_once(' ')
+        && KEYWORDS.contains(&first)
+    {
+        title = after.trim_start();
+    }
+    if let Some(after) = title.strip_prefix("[#")
+        && let Some((_, after)) = after.split_once("] ")
+    {
+        title = after.trim_start();
+    }
+    Some(Heading { level, title: title.to_string(), tags, ..Default::default() })
+}
+
+/// Reads an org timestamp such as `[2026-10-15 Thu 09:00]`; the weekday is
+/// optional.
+fn parse_stamp(stamp: &str, calendar: &Calendar) -> Result<DateTime<FixedOffset>, String> {
+    let invalid = || format!("invalid timestamp {:?}", stamp);
+    let inner = stamp.trim().strip_prefix('[').and_then(|s| s.strip_suffix(']')).ok_or_else(invalid)?;
+    let parts: Vec<&str> = inner.split_whitespace().collect();
+    let (date, time) = match parts.as_slice() {
+        [date, _, time] | [date, time] => (date, time),
+        _ => return Err(invalid()),
+    };
+    let naive = NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").map_err(|_| invalid())?;
+    calendar.localize(naive).ok_or_else(|| format!("time {:?} does not exist in this timezone", stamp))
+}
+
+fn read_clock(value: &str, headings: &[Heading], calendar: &Calendar) -> Result<Activity, String> {
+    let heading = headings.last().ok_or("clock line outside a heading")?;
+    let range = value.split("=>").next().unwrap_or("").trim();
+    let Some((start, end)) = range.split_once("--") else {
+        return Err("clock is still running".to_string());
+    };
+    let start = parse_stamp(start, calendar)?;
+    let end = parse_stamp(end, calendar)?;
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in headings.iter().flat_map(|h| &h.tags) {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    Ok(Activity {
+        name: heading.title.clone(),
+        duration_minutes: minutes_between(start, end)?,
+        date: calendar.to_local(start).date().format("%Y-%m-%d").to_string(),
+        started_at: Some(start),
+        project: headings.iter().rev().find_map(|h| h.project.clone()),
+        billable: headings.iter().rev().find_map(|h| h.billable),
+        tags,
+        ..Default::default()
+    })
+}
+
+/// Reads every `CLOCK:` line. Tags, `PROJECT` and `BILLABLE` properties are
+/// inherited from enclosing headings, as in org itself.
+pub fn import(text: &str, calendar: &Calendar) -> Vec<ImportRow> {
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut in_properties = false;
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if let Some(heading) = parse_heading(line) {
+            headings.retain(|h| h.level < heading.level);
+            headings.push(heading);
+            in_properties = false;
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.eq_ignore_ascii_case(":PROPERTIES:") {
+            in_properties = true;
+        } else if trimmed.eq_ignore_ascii_case(":END:") {
+            in_properties = false;
+        } else if in_properties {
+            let Some((key, value)) = trimmed.strip_prefix(':').and_then(|s| s.split_once(':')) else { continue };
+            let Some(heading) = headings.last_mut() else { continue };
+            let value = value.trim();
+            match key.to_ascii_uppercase().as_str() {
+                "PROJECT" if !value.is_empty() => heading.project = Some(value.to_string()),
+                "BILLABLE" => heading.billable = parse_bool(value).ok(),
+                _ => {}
+            }
+        } else if let Some(value) = trimmed.strip_prefix("CLOCK:") {
+            rows.push(ImportRow { file: None, line: i + 1, result: read_clock(value, &headings, calendar) });
+        }
+    }
+    rows
+}
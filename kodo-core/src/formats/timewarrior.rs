@@ -0,0 +1,211 @@
+//! Timewarrior's `data/YYYY-MM.data` interval files.
+//!
+//! Each line is one interval:
+//!
+//! ```text
+//! inc 20261015T090000Z - 20261015T103000Z # tag1 "tag two" # "annotation"
+//! inc 20261016T080000Z # still-running
+//! ```
+//!
+//! The annotation becomes the activity name. A project is kept as a
+//! `project:NAME` tag and an explicit billable flag as a `billable` or
+//! `non-billable` tag, so exported history imports back unchanged.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, Utc};
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use super::{minutes_between, ImportRow};
+
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const PROJECT_PREFIX: &str = "project:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    /// A double-quoted string, unescaped.
+    Quoted(String),
+}
+
+impl Token {
+    fn text(&self) -> &str {
+        match self {
+            Token::Word(s) | Token::Quoted(s) => s,
+        }
+    }
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => text.extend(chars.next()),
+                    Some('"') => break,
+                    Some(c) => text.push(c),
+                    None => return Err("unterminated quote".to_string()),
+                }
+            }
+            tokens.push(Token::Quoted(text));
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_time(s: &str) -> Result<DateTime<FixedOffset>, String> {
+    NaiveDateTime::parse_from_str(s, TIME_FORMAT)
+        .map(|t| t.and_utc().fixed_offset())
+        .map_err(|_| format!("invalid timestamp {:?}", s))
+}
+
+/// Reads one interval line. Open intervals run until `now`.
+fn parse_line(line: &str, now: DateTime<Utc>, calendar: &Calendar) -> Result<Activity, String> {
+    let tokens = tokenize(line)?;
+    let mut tokens = tokens.iter().peekable();
+    if tokens.next().map(Token::text) != Some("inc") {
+        return Err("expected a line starting with \"inc\"".to_string());
+    }
+    let start = parse_time(tokens.next().ok_or("missing start time")?.text())?;
+    let end = match tokens.peek() {
+        Some(Token::Word(dash)) if dash == "-" => {
+            tokens.next();
+            parse_time(tokens.next().ok_or("missing end time")?.text())?
+        }
+        _ => now.fixed_offset(),
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    let mut annotation = None;
+    if let Some(Token::Word(hash)) = tokens.next() {
+        if hash != "#" {
+            return Err(format!("unexpected {:?}", hash));
+        }
+        while let Some(token) = tokens.next() {
+            match token {
+                Token::Word(w) if w == "#" => {
+                    annotation = tokens.next().map(|t| t.text().to_string());
+                    break;
+                }
+                other => tags.push(other.text().to_string()),
+            }
+        }
+    }
+
+    let mut project = None;
+    let mut billable = None;
+    tags.retain(|tag| {
+        if let Some(name) = tag.strip_prefix(PROJECT_PREFIX) {
+            project = Some(name.to_string());
+            return false;
+        }
+        match tag.as_str() {
+            "billable" => billable = Some(true),
+            "non-billable" => billable = Some(false),
+            _ => return true,
+        }
+        false
+    });
+
+    let name = annotation
+        .filter(|a| !a.trim().is_empty())
+        .unwrap_or_else(|| if tags.is_empty() { "(untagged)".to_string() } else { tags.join(" ") });
+    Ok(Activity {
+        name,
+        duration_minutes: minutes_between(start, end)?,
+        date: calendar.to_local(start).date().format("%Y-%m-%d").to_string(),
+        started_at: Some(start),
+        project,
+        billable,
+        tags,
+        ..Default::default()
+    })
+}
+
+/// Reads the intervals in one data file. `file` is used in the report.
+pub fn import(text: &str, file: Option<&str>, now: DateTime<Utc>, calendar: &Calendar) -> Vec<ImportRow> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| ImportRow {
+            file: file.map(str::to_string),
+            line: i + 1,
+            result: parse_line(line, now, calendar),
+        })
+        .collect()
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn quote_if_needed(s: &str) -> String {
+    if !s.is_empty() && !s.contains(|c: char| c.is_whitespace() || c == '"' || c == '#') {
+        return s.to_string();
+    }
+    quote(s)
+}
+
+/// One interval line for an activity. Activities without a start time
+/// begin at midnight of their day.
+fn interval(act: &Activity, calendar: &Calendar) -> Option<(DateTime<Utc>, String)> {
+    let start = match act.started_at {
+        Some(started) => started.with_timezone(&Utc),
+        None => calendar.localize(calendar.day_of(act)?.and_hms_opt(0, 0, 0)?)?.with_timezone(&Utc),
+    };
+    let end = start + Duration::minutes(act.duration_minutes as i64);
+
+    let mut tags: Vec<String> = act.tags.clone();
+    if let Some(project) = &act.project {
+        tags.push(format!("{}{}", PROJECT_PREFIX, project));
+    }
+    match act.billable {
+        Some(true) => tags.push("billable".to_string()),
+        Some(false) => tags.push("non-billable".to_string()),
+        None => {}
+    }
+
+    let mut line = format!("inc {} - {} #", start.format(TIME_FORMAT), end.format(TIME_FORMAT));
+    for tag in &tags {
+        line.push(' ');
+        line.push_str(&quote_if_needed(tag));
+    }
+    // Timewarrior always quotes annotations
+    line.push_str(&format!(" # {}", quote(act.name.lines().next().unwrap_or(""))));
+    Some((start, line))
+}
+
+/// Intervals grouped into monthly files, as `(file name, contents)` pairs
+/// such as `("2026-10.data", ...)`, oldest first.
+pub fn export(activities: &[Activity], calendar: &Calendar) -> Vec<(String, String)> {
+    let mut lines: Vec<(DateTime<Utc>, String)> = activities.iter().filter_map(|a| interval(a, calendar)).collect();
+    lines.sort_by_key(|(start, _)| *start);
+
+    let mut files: Vec<(String, String)> = Vec::new();
+    for (start, line) in lines {
+        let name = start.format("%Y-%m.data").to_string();
+        match files.last_mut() {
+            Some((file, contents)) if *file == name => {
+                contents.push_str(&line);
+                contents.push('\n');
+            }
+            _ => files.push((name, format!("{}\n", line))),
+        }
+    }
+    files
+}
@@ -0,0 +1,37 @@
+//! Toggl Track's detailed report CSV.
+
+use anyhow::Result;
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::config::Config;
+use super::detailed::{self, Layout};
+use super::ImportRow;
+
+const LAYOUT: Layout = Layout {
+    tool: "Toggl",
+    header: &[
+        "User", "Email", "Client", "Project", "Task", "Description", "Billable",
+        "Start date", "Start time", "End date", "End time", "Duration", "Tags",
+    ],
+    description: "Description",
+    project: "Project",
+    tags: "Tags",
+    billable: "Billable",
+    start_date: "Start date",
+    start_time: "Start time",
+    end_date: "End date",
+    end_time: "End time",
+    duration: "Duration",
+    decimal_duration: None,
+    date_format: "%Y-%m-%d",
+    time_format: "%H:%M:%S",
+};
+
+pub fn import(text: &str, calendar: &Calendar) -> Result<Vec<ImportRow>> {
+    detailed::import(text, &LAYOUT, calendar)
+}
+
+pub fn export(activities: &[Activity], config: &Config) -> Result<String> {
+    detailed::export(activities, &LAYOUT, config)
+}
@@ -0,0 +1,128 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Serialize, Deserialize};
+
+use crate::activity::Activity;
+use crate::calendar::{Calendar, Period};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalDirection {
+    /// Spend at least `minutes` per window, e.g. deep work.
+    #[default]
+    Min,
+    /// Spend no more than `minutes` per window, e.g. meetings.
+    Max,
+}
+
+/// A time target, stored in the config. A goal applies to a tag, a project,
+/// or (with neither set) to all activities.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    pub window: Period,
+    pub minutes: u32,
+    #[serde(default)]
+    pub direction: GoalDirection,
+}
+
+impl Goal {
+    pub fn applies_to(&self, activity: &Activity) -> bool {
+        let tag_ok = self.tag.as_ref().is_none_or(|t| activity.tags.iter().any(|at| at.eq_ignore_ascii_case(t)));
+        let project_ok = self.project.as_ref().is_none_or(|p| activity.project().is_some_and(|ap| ap.eq_ignore_ascii_case(p)));
+        tag_ok && project_ok
+    }
+
+    /// What the goal counts, e.g. `tag deep-work` or `all activities`.
+    pub fn scope(&self) -> String {
+        match (&self.tag, &self.project) {
+            (Some(tag), Some(project)) => format!("tag {} in project {}", tag, project),
+            (Some(tag), None) => format!("tag {}", tag),
+            (None, Some(project)) => format!("project {}", project),
+            (None, None) => "all activities".to_string(),
+        }
+    }
+
+    pub fn window_name(&self) -> &'static str {
+        match self.window {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatus {
+    /// A minimum target has been reached.
+    Met,
+    /// On pace to reach a minimum, or comfortably within a maximum.
+    OnTrack,
+    /// Behind pace for a minimum, or using a budget faster than time passes.
+    AtRisk,
+    /// A maximum has been exceeded.
+    Over,
+}
+
+/// Where a goal stands in the current window.
+#[derive(Debug, Clone)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    pub period_start: NaiveDate,
+    /// First day after the window.
+    pub period_end: NaiveDate,
+    pub minutes: u32,
+    /// Share of the window that has already passed, from 0 to 1.
+    pub elapsed: f64,
+    pub status: GoalStatus,
+}
+
+impl GoalProgress {
+    pub fn compute(goal: &Goal, activities: &[Activity], calendar: &Calendar, now: NaiveDateTime) -> Self {
+        let today = now.date();
+        let period_start = calendar.period_start(today, goal.window);
+        let period_end = calendar.period_end(period_start, goal.window);
+
+        let minutes = activities
+            .iter()
+            .filter(|a| goal.applies_to(a))
+            .filter(|a| calendar.day_of(a).is_some_and(|d| d >= period_start && d < period_end))
+            .map(|a| a.duration_minutes)
+            .sum();
+
+        let length = (period_end - period_start).num_seconds() as f64;
+        let passed = (now - period_start.and_hms_opt(0, 0, 0).unwrap()).num_seconds() as f64;
+        let elapsed = (passed / length).clamp(0.0, 1.0);
+
+        let done = minutes as f64 / goal.minutes.max(1) as f64;
+        let status = match goal.direction {
+            GoalDirection::Min if minutes >= goal.minutes => GoalStatus::Met,
+            GoalDirection::Min if done >= elapsed => GoalStatus::OnTrack,
+            GoalDirection::Min => GoalStatus::AtRisk,
+            GoalDirection::Max if minutes > goal.minutes => GoalStatus::Over,
+            GoalDirection::Max if done > elapsed => GoalStatus::AtRisk,
+            GoalDirection::Max => GoalStatus::OnTrack,
+        };
+
+        Self { goal: goal.clone(), period_start, period_end, minutes, elapsed, status }
+    }
+
+    /// Progress towards the target, from 0 upwards (above 1 once reached).
+    pub fn ratio(&self) -> f64 {
+        self.minutes as f64 / self.goal.minutes.max(1) as f64
+    }
+
+    pub fn status_label(&self) -> &'static str {
+        match (self.status, self.goal.direction) {
+            (GoalStatus::Met, _) => "met",
+            (GoalStatus::OnTrack, GoalDirection::Min) => "on track",
+            (GoalStatus::OnTrack, GoalDirection::Max) => "within budget",
+            (GoalStatus::AtRisk, GoalDirection::Min) => "behind",
+            (GoalStatus::AtRisk, GoalDirection::Max) => "spending fast",
+            (GoalStatus::Over, _) => "over budget",
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{Days, NaiveDate};
+
+use crate::activity::Activity;
+use crate::calendar::{Calendar, Period};
+
+/// xterm-256 colour indices for intensity levels 0 (nothing) to 4.
+pub const PALETTE: [u8; 5] = [237, 22, 28, 34, 40];
+/// Longest span a heatmap covers, about ten years.
+pub const MAX_WEEKS: usize = 520;
+
+/// Minutes per day over a number of whole weeks ending with the current one,
+/// laid out like GitHub's contribution graph: one column per week.
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    /// First day of the first week shown.
+    pub start: NaiveDate,
+    pub today: NaiveDate,
+    pub weeks: usize,
+    /// Minutes for each day from `start`, `weeks * 7` entries.
+    pub minutes: Vec<u32>,
+    /// Minutes on the busiest day.
+    busiest: u32,
+}
+
+impl Heatmap {
+    /// Covers `weeks` weeks, between 1 and `MAX_WEEKS`.
+    pub fn build(activities: &[Activity], calendar: &Calendar, today: NaiveDate, weeks: usize) -> Self {
+        let weeks = weeks.clamp(1, MAX_WEEKS);
+        let this_week = calendar.period_start(today, Period::Week);
+        let start = this_week - Days::new(7 * (weeks as u64 - 1));
+
+        let mut minutes = vec![0; weeks * 7];
+        for act in activities {
+            let Some(day) = calendar.day_of(act) else { continue };
+            if day < start || day > today {
+                continue;
+            }
+            let index = (day - start).num_days() as usize;
+            minutes[index] = u32::saturating_add(minutes[index], act.duration_minutes);
+        }
+
+        let busiest = minutes.iter().copied().max().unwrap_or(0);
+        Self { start, today, weeks, minutes, busiest }
+    }
+
+    pub fn max_minutes(&self) -> u32 {
+        self.busiest
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.minutes.iter().sum()
+    }
+
+    /// The day in `week` (0 = oldest) at `row` (0 = first day of the week),
+    /// with its minutes. `None` for days after today.
+    pub fn cell(&self, week: usize, row: usize) -> Option<(NaiveDate, u32)> {
+        let index = week * 7 + row;
+        let day = self.start + Days::new(index as u64);
+        (day <= self.today).then(|| (day, self.minutes[index]))
+    }
+
+    /// Intensity from 0 (no activity) to 4, relative to the busiest day.
+    pub fn level(&self, minutes: u32) -> usize {
+        if minutes == 0 || self.busiest == 0 {
+            return 0;
+        }
+        (minutes as u64 * 4).div_ceil(self.busiest as u64).clamp(1, 4) as usize
+    }
+}
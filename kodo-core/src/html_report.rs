@@ -0,0 +1,309 @@
+//! A self-contained HTML summary with inline SVG charts, for sharing with
+//! people who don't use a terminal.
+
+use anyhow::Result;
+use chrono::{Datelike, Days, NaiveDate};
+use std::f64::consts::PI;
+
+use crate::activity::Activity;
+use crate::analytics::Analytics;
+use crate::calendar::Period;
+use crate::compare::DateRange;
+use crate::config::Config;
+use crate::heatmap::Heatmap;
+use crate::html::escape;
+use crate::report::billable_split;
+use crate::streaks::StreakStats;
+use crate::timesheet::{format_hours, NO_PROJECT};
+
+/// Slice colours of the project chart; further projects share the last one.
+const PIE_COLORS: [&str; 8] = ["#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#bab0ac"];
+/// Heatmap cell colours for intensity levels 0 to 4.
+const HEAT_COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// Commits from one repository.
+#[derive(Debug, Clone)]
+pub struct RepoCommits {
+    pub repo: String,
+    pub commits: usize,
+    pub minutes: u32,
+}
+
+/// Everything shown in the HTML report for a range of days.
+#[derive(Debug, Clone)]
+pub struct HtmlReport {
+    pub range: DateRange,
+    /// Activities in the range with the day they count towards, oldest first.
+    pub activities: Vec<(NaiveDate, Activity)>,
+    /// Minutes per day, one entry per day of the range.
+    pub daily: Vec<(NaiveDate, u32)>,
+    /// Minutes per project, largest first.
+    pub projects: Vec<(String, u32)>,
+    pub commits: Vec<RepoCommits>,
+    pub billable_minutes: u32,
+    pub analytics: Analytics,
+    pub streaks: StreakStats,
+    pub heatmap: Heatmap,
+}
+
+impl HtmlReport {
+    pub fn build(activities: &[Activity], range: DateRange, config: &Config) -> Result<Self> {
+        let calendar = config.calendar()?;
+        let mut selected: Vec<(NaiveDate, Activity)> = activities
+            .iter()
+            .filter_map(|a| Some((calendar.day_of(a).filter(|d| range.contains(*d))?, a.clone())))
+            .collect();
+        selected.sort_by_key(|(day, a)| (*day, a.started_at, a.id));
+
+        let daily = (0..range.days())
+            .map(|i| {
+                let day = range.from + Days::new(i);
+                let minutes = selected.iter().filter(|(d, _)| *d == day).map(|(_, a)| a.duration_minutes).sum();
+                (day, minutes)
+            })
+            .collect();
+
+        let mut projects: Vec<(String, u32)> = Vec::new();
+        let mut commits: Vec<RepoCommits> = Vec::new();
+        for (_, act) in &selected {
+            let project = act.project.clone().unwrap_or_else(|| NO_PROJECT.to_string());
+            match projects.iter_mut().find(|(p, _)| *p == project) {
+                Some((_, minutes)) => *minutes += act.duration_minutes,
+                None => projects.push((project, act.duration_minutes)),
+            }
+            if act.is_from_git() {
+                let repo = act.source.as_ref().and_then(|s| s.repo.clone()).unwrap_or_else(|| "(unknown)".to_string());
+                match commits.iter_mut().find(|c| c.repo == repo) {
+                    Some(entry) => {
+                        entry.commits += 1;
+                        entry.minutes += act.duration_minutes;
+                    }
+                    None => commits.push(RepoCommits { repo, commits: 1, minutes: act.duration_minutes }),
+                }
+            }
+        }
+        projects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        commits.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.repo.cmp(&b.repo)));
+
+        let week_start = calendar.period_start(range.from, Period::Week);
+        let weeks = ((range.to - week_start).num_days() / 7 + 1) as usize;
+        let acts: Vec<Activity> = selected.iter().map(|(_, a)| a.clone()).collect();
+        Ok(Self {
+            range,
+            daily,
+            projects,
+            commits,
+            billable_minutes: billable_split(&acts, config).0,
+            analytics: Analytics::compute(&acts, &calendar),
+            streaks: StreakStats::compute(&acts, &calendar, range.to),
+            heatmap: Heatmap::build(&acts, &calendar, range.to, weeks),
+            activities: selected,
+        })
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.daily.iter().map(|(_, m)| m).sum()
+    }
+
+    pub fn title(&self) -> String {
+        format!("Activity report {} to {}", self.range.from, self.range.to)
+    }
+
+    pub fn to_html(&self) -> String {
+        let total = self.total_minutes();
+        let d = &self.analytics.durations;
+        let summary = [
+            ("Total time", format_hours(total)),
+            ("Activities", self.activities.len().to_string()),
+            ("Active days", self.daily.iter().filter(|(_, m)| *m > 0).count().to_string()),
+            ("Billable", format_hours(self.billable_minutes)),
+            ("Non-billable", format_hours(total - self.billable_minutes)),
+            ("Commits", self.commits.iter().map(|c| c.commits).sum::<usize>().to_string()),
+            ("Median activity", format!("{:.0} min", d.median)),
+            ("90th percentile", format!("{:.0} min", d.p90)),
+            ("Longest streak", format!("{} days", self.streaks.longest)),
+        ];
+        let summary: String = summary
+            .iter()
+            .map(|(label, value)| format!("<div class=\"stat\"><span>{}</span><strong>{}</strong></div>\n", label, value))
+            .collect();
+
+        let mut projects = String::new();
+        for (project, minutes) in &self.projects {
+            projects.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.0}%</td></tr>\n",
+                escape(project),
+                format_hours(*minutes),
+                *minutes as f64 * 100.0 / total.max(1) as f64,
+            ));
+        }
+
+        let mut commits = String::new();
+        for entry in &self.commits {
+            commits.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape(&entry.repo),
+                entry.commits,
+                format_hours(entry.minutes),
+            ));
+        }
+        if commits.is_empty() {
+            commits.push_str("<tr><td colspan=\"3\">No commits in this range.</td></tr>\n");
+        }
+
+        let mut activities = String::new();
+        for (day, act) in &self.activities {
+            activities.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td></tr>\n",
+                day,
+                escape(act.name.lines().next().unwrap_or("")),
+                escape(act.project.as_deref().unwrap_or("")),
+                escape(&act.tags.join(", ")),
+                format_hours(act.duration_minutes),
+            ));
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+             body {{ font-family: sans-serif; margin: 2em; color: #24292f; }}\n\
+             h2 {{ margin-top: 1.5em; }}\n\
+             table {{ border-collapse: collapse; width: 100%; }}\n\
+             th, td {{ border-bottom: 1px solid #ccc; padding: 4px 8px; text-align: left; }}\n\
+             .num {{ text-align: right; }}\n\
+             .stats {{ display: flex; flex-wrap: wrap; gap: 1em; }}\n\
+             .stat {{ border: 1px solid #ccc; border-radius: 6px; padding: 0.5em 1em; }}\n\
+             .stat span {{ display: block; font-size: 0.8em; color: #57606a; }}\n\
+             .charts {{ display: flex; flex-wrap: wrap; gap: 2em; align-items: flex-start; }}\n\
+             svg text {{ font-family: sans-serif; font-size: 10px; fill: #57606a; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+             <div class=\"stats\">\n{summary}</div>\n\
+             <h2>Time per day</h2>\n{daily}\n\
+             <div class=\"charts\">\n<div>\n<h2>Project share</h2>\n{pie}\n</div>\n<div>\n<h2>Activity</h2>\n{heatmap}\n</div>\n</div>\n\
+             <h2>Projects</h2>\n<table>\n<tr><th>Project</th><th class=\"num\">Hours</th><th class=\"num\">Share</th></tr>\n{projects}</table>\n\
+             <h2>Commits</h2>\n<table>\n<tr><th>Repository</th><th class=\"num\">Commits</th><th class=\"num\">Hours</th></tr>\n{commits}</table>\n\
+             <h2>Activities</h2>\n<table>\n<tr><th>Date</th><th>Activity</th><th>Project</th><th>Tags</th><th class=\"num\">Hours</th></tr>\n{activities}</table>\n\
+             </body>\n</html>\n",
+            title = escape(&self.title()),
+            summary = summary,
+            daily = self.daily_svg(),
+            pie = self.pie_svg(),
+            heatmap = self.heatmap_svg(),
+            projects = projects,
+            commits = commits,
+            activities = activities,
+        )
+    }
+
+    /// A bar per day of the range.
+    fn daily_svg(&self) -> String {
+        let (width, height, bottom) = (720.0, 180.0, 20.0);
+        let max = self.daily.iter().map(|(_, m)| *m).max().unwrap_or(0).max(1) as f64;
+        let slot = width / self.daily.len().max(1) as f64;
+        let label_every = if self.daily.len() > 31 { 7 } else { 1 };
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" role=\"img\">\n", width, height);
+        for (i, (day, minutes)) in self.daily.iter().enumerate() {
+            let bar = *minutes as f64 / max * (height - bottom - 10.0);
+            let x = i as f64 * slot;
+            svg.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#4e79a7\"><title>{} {}</title></rect>\n",
+                x + slot * 0.1,
+                height - bottom - bar,
+                slot * 0.8,
+                bar,
+                day,
+                format_hours(*minutes),
+            ));
+            if i % label_every == 0 {
+                svg.push_str(&format!(
+                    "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n",
+                    x + slot / 2.0,
+                    height - 5.0,
+                    day.day(),
+                ));
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Share of time per project, with a legend.
+    fn pie_svg(&self) -> String {
+        let (cx, cy, r) = (100.0, 100.0, 90.0);
+        let total = self.total_minutes();
+        let mut slices: Vec<(String, u32)> = self.projects.iter().take(PIE_COLORS.len() - 1).cloned().collect();
+        let rest: u32 = self.projects.iter().skip(PIE_COLORS.len() - 1).map(|(_, m)| m).sum();
+        if rest > 0 {
+            slices.push(("Other".to_string(), rest));
+        }
+
+        let height = (slices.len() as f64 * 18.0 + 10.0).max(2.0 * cy);
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"420\" height=\"{}\" role=\"img\">\n", height);
+        if total == 0 {
+            svg.push_str(&format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n", cx, cy, r, HEAT_COLORS[0]));
+        }
+        let mut angle = -PI / 2.0;
+        for (i, (project, minutes)) in slices.iter().enumerate() {
+            let color = PIE_COLORS[i.min(PIE_COLORS.len() - 1)];
+            let share = *minutes as f64 / total.max(1) as f64;
+            let label = format!("{} {} ({:.0}%)", escape(project), format_hours(*minutes), share * 100.0);
+            if share >= 1.0 {
+                svg.push_str(&format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"><title>{}</title></circle>\n", cx, cy, r, color, label));
+            } else if share > 0.0 {
+                let end = angle + share * 2.0 * PI;
+                svg.push_str(&format!(
+                    "<path d=\"M {cx} {cy} L {:.2} {:.2} A {r} {r} 0 {} 1 {:.2} {:.2} Z\" fill=\"{}\"><title>{}</title></path>\n",
+                    cx + r * angle.cos(),
+                    cy + r * angle.sin(),
+                    u8::from(share > 0.5),
+                    cx + r * end.cos(),
+                    cy + r * end.sin(),
+                    color,
+                    label,
+                ));
+                angle = end;
+            }
+            let y = 10.0 + i as f64 * 18.0;
+            svg.push_str(&format!(
+                "<rect x=\"210\" y=\"{}\" width=\"12\" height=\"12\" fill=\"{}\"/><text x=\"228\" y=\"{}\">{}</text>\n",
+                y, color, y + 10.0, label,
+            ));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Minutes per day laid out by week, like the terminal heatmap.
+    fn heatmap_svg(&self) -> String {
+        let (cell, gap, left) = (12.0, 2.0, 30.0);
+        let heatmap = &self.heatmap;
+        let width = left + heatmap.weeks as f64 * (cell + gap);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" role=\"img\">\n",
+            width,
+            7.0 * (cell + gap),
+        );
+        for row in (0..7).step_by(2) {
+            let day = heatmap.start + Days::new(row as u64);
+            svg.push_str(&format!("<text x=\"0\" y=\"{}\">{}</text>\n", row as f64 * (cell + gap) + 10.0, day.format("%a")));
+        }
+        for week in 0..heatmap.weeks {
+            for row in 0..7 {
+                let Some((day, minutes)) = heatmap.cell(week, row) else { continue };
+                if day < self.range.from {
+                    continue;
+                }
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{cell}\" height=\"{cell}\" rx=\"2\" fill=\"{}\"><title>{} {}</title></rect>\n",
+                    left + week as f64 * (cell + gap),
+                    row as f64 * (cell + gap),
+                    HEAT_COLORS[heatmap.level(minutes)],
+                    day,
+                    format_hours(minutes),
+                ));
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
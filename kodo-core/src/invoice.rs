@@ -0,0 +1,252 @@
+use chrono::NaiveDate;
+use serde::{Serialize, Deserialize};
+use std::path::Path;
+use anyhow::{Result, Context, bail};
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::config::Config;
+use crate::html::escape;
+use crate::timesheet::format_hours;
+
+/// Billing settings for a project, keyed by project name in the config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Hourly rate in `currency`.
+    pub rate: Option<f64>,
+    pub currency: Option<String>,
+    /// Whether time on this project is billable unless an activity says
+    /// otherwise. Defaults to billable.
+    pub billable: Option<bool>,
+}
+
+/// An invoice that has been issued, kept so numbering stays sequential.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceRecord {
+    pub number: String,
+    pub project: String,
+    pub issued: String,
+    pub from: String,
+    pub to: String,
+    pub currency: String,
+    /// Sum of the line amounts, in cents.
+    pub total_cents: i64,
+    pub activity_ids: Vec<u32>,
+}
+
+/// All issued invoices, stored as JSON next to the activities file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InvoiceLedger {
+    pub invoices: Vec<InvoiceRecord>,
+}
+
+impl InvoiceLedger {
+    pub fn load_from_file(path: &Path) -> Result<InvoiceLedger> {
+        if !path.exists() {
+            return Ok(InvoiceLedger::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read invoice ledger {:?}", path))?;
+        if contents.trim().is_empty() {
+            return Ok(InvoiceLedger::default());
+        }
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse JSON in {:?}", path))
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Records `invoice` as issued and marks its activities with its number,
+    /// so later invoices leave them out.
+    pub fn issue(&mut self, invoice: &Invoice, activities: &mut [Activity]) {
+        for item in &invoice.items {
+            if let Some(act) = activities.iter_mut().find(|a| a.id == item.activity_id) {
+                act.invoice = Some(invoice.number.clone());
+            }
+        }
+        self.invoices.push(invoice.record());
+    }
+
+    /// The number the next invoice gets, e.g. `INV-0007`.
+    pub fn next_number(&self) -> String {
+        let last = self.invoices
+            .iter()
+            .filter_map(|i| i.number.strip_prefix("INV-")?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0);
+        format!("INV-{:04}", last + 1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub activity_id: u32,
+    pub date: NaiveDate,
+    pub description: String,
+    /// Rounded billable minutes.
+    pub minutes: u32,
+    /// Rate times `minutes`, rounded once to whole cents.
+    pub amount_cents: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    pub number: String,
+    pub project: String,
+    pub issued: NaiveDate,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub rate: f64,
+    pub currency: String,
+    pub items: Vec<LineItem>,
+}
+
+/// Formats cents as a decimal amount, e.g. `1234` as `12.34`.
+pub fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, cents.unsigned_abs() / 100, cents.unsigned_abs() % 100)
+}
+
+impl Invoice {
+    /// Bills every billable activity of `project` between `from` and `to`
+    /// (inclusive) that isn't on an earlier invoice yet, at the project's
+    /// rate and with the configured rounding.
+    pub fn build(
+        number: &str,
+        activities: &[Activity],
+        project: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        config: &Config,
+        calendar: &Calendar,
+    ) -> Result<Invoice> {
+        let settings = config.project(project);
+        let Some(rate) = settings.rate else {
+            bail!("No hourly rate configured for project {:?}", project);
+        };
+
+        let mut items: Vec<LineItem> = activities
+            .iter()
+            .filter(|a| a.invoice.is_none())
+            .filter(|a| config.is_billable(a))
+            .filter(|a| a.project().is_some_and(|p| p.eq_ignore_ascii_case(project)))
+            .filter_map(|a| {
+                let date = calendar.day_of(a)?;
+                if date < from || date > to {
+                    return None;
+                }
+                let minutes = config.rounding.apply(a.duration_minutes);
+                Some(LineItem {
+                    activity_id: a.id,
+                    date,
+                    description: a.name.lines().next().unwrap_or("").trim().to_string(),
+                    minutes,
+                    amount_cents: (minutes as f64 * rate * 100.0 / 60.0).round() as i64,
+                })
+            })
+            .collect();
+        items.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.activity_id.cmp(&b.activity_id)));
+
+        Ok(Invoice {
+            number: number.to_string(),
+            project: project.to_string(),
+            issued: calendar.today(),
+            from,
+            to,
+            rate,
+            currency: settings.currency.unwrap_or_else(|| "USD".to_string()),
+            items,
+        })
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.items.iter().map(|i| i.minutes).sum()
+    }
+
+    /// Sum of the line amounts. Each line is rounded to cents on its own,
+    /// so the total always matches the lines printed above it.
+    pub fn total_cents(&self) -> i64 {
+        self.items.iter().map(|i| i.amount_cents).sum()
+    }
+
+    pub fn record(&self) -> InvoiceRecord {
+        InvoiceRecord {
+            number: self.number.clone(),
+            project: self.project.clone(),
+            issued: self.issued.to_string(),
+            from: self.from.to_string(),
+            to: self.to.to_string(),
+            currency: self.currency.clone(),
+            total_cents: self.total_cents(),
+            activity_ids: self.items.iter().map(|i| i.activity_id).collect(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Invoice {}\n\n", self.number);
+        out.push_str(&format!("- **Project:** {}\n", self.project));
+        out.push_str(&format!("- **Issued:** {}\n", self.issued));
+        out.push_str(&format!("- **Period:** {} to {}\n", self.from, self.to));
+        out.push_str(&format!("- **Rate:** {:.2} {} / hour\n\n", self.rate, self.currency));
+        out.push_str("| Date | Description | Hours | Amount |\n");
+        out.push_str("| ---- | ----------- | ----: | -----: |\n");
+        for item in &self.items {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                item.date,
+                item.description.replace('|', "\\|"),
+                format_hours(item.minutes),
+                format_cents(item.amount_cents),
+            ));
+        }
+        out.push_str(&format!(
+            "| | **Total** | **{}** | **{} {}** |\n",
+            format_hours(self.total_minutes()),
+            format_cents(self.total_cents()),
+            self.currency,
+        ));
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let rows: String = self.items
+            .iter()
+            .map(|item| format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                item.date,
+                escape(&item.description),
+                format_hours(item.minutes),
+                format_cents(item.amount_cents),
+            ))
+            .collect();
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Invoice {number}</title>\n<style>\n\
+             body {{ font-family: sans-serif; margin: 2em; }}\n\
+             table {{ border-collapse: collapse; width: 100%; margin-top: 1em; }}\n\
+             th, td {{ border-bottom: 1px solid #ccc; padding: 4px 8px; text-align: left; }}\n\
+             .num {{ text-align: right; }}\n\
+             .total td {{ font-weight: bold; border-top: 2px solid #333; }}\n\
+             </style>\n</head>\n<body>\n<h1>Invoice {number}</h1>\n\
+             <p>Project: {project}<br>Issued: {issued}<br>Period: {from} to {to}<br>Rate: {rate:.2} {currency} / hour</p>\n\
+             <table>\n<tr><th>Date</th><th>Description</th><th class=\"num\">Hours</th><th class=\"num\">Amount</th></tr>\n\
+             {rows}<tr class=\"total\"><td></td><td>Total</td><td class=\"num\">{hours}</td><td class=\"num\">{total} {currency}</td></tr>\n\
+             </table>\n</body>\n</html>\n",
+            number = escape(&self.number),
+            project = escape(&self.project),
+            issued = self.issued,
+            from = self.from,
+            to = self.to,
+            rate = self.rate,
+            currency = escape(&self.currency),
+            rows = rows,
+            hours = format_hours(self.total_minutes()),
+            total = format_cents(self.total_cents()),
+        )
+    }
+}
@@ -1,4 +1,24 @@
 pub mod activity;
+pub mod analytics;
+pub mod calendar;
+pub mod compare;
+pub mod config;
+pub mod formats;
+pub mod goals;
+pub mod heatmap;
+mod html;
+pub mod html_report;
+pub mod invoice;
+pub mod output;
+pub mod report;
+pub mod sessions;
+pub mod standup;
+pub mod streaks;
+pub mod tickets;
+pub mod timesheet;
+pub mod worklog;
 
-pub use activity::Activity;
-
+pub use activity::{Activity, Source, SourceKind};
+pub use calendar::{Calendar, Period};
+pub use config::Config;
+pub use tickets::TicketMatcher;
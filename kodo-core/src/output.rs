@@ -0,0 +1,579 @@
+//! Machine-readable output for read commands.
+//!
+//! Every read command writes the same document shape so scripts can rely on
+//! it:
+//!
+//! - `json`: `{"schema": 1, "kind": "activity", "items": [...], "totals": {...}}`
+//! - `jsonl`: one item per line tagged with its `kind`, then a line with
+//!   `"kind": "totals"`
+//! - `csv`: the items only, with a header row
+//!
+//! Field names below are part of that schema; add fields rather than
+//! renaming or removing them.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+
+use crate::activity::{Activity, SourceKind};
+use crate::analytics::{Analytics, DurationStats};
+use crate::calendar::{Calendar, Period};
+use crate::compare::{percent_change, Comparison};
+use crate::config::Config;
+use crate::goals::{GoalDirection, GoalProgress, GoalStatus};
+use crate::heatmap::Heatmap;
+use crate::report::{billable_split, summarize_by_period, PeriodSummary};
+use crate::standup::Standup;
+use crate::streaks::StreakStats;
+
+/// Version of the document layout, bumped on incompatible changes.
+const SCHEMA: u32 = 1;
+
+/// A machine-readable format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One JSON document with items and totals
+    Json,
+    /// One JSON object per line, totals last
+    Jsonl,
+    /// Items as CSV
+    Csv,
+}
+
+/// An item of machine-readable output.
+pub trait Record: Serialize {
+    /// Tags each item in JSON output.
+    const KIND: &'static str;
+    /// Fields written as CSV columns, in order.
+    const COLUMNS: &'static [&'static str];
+}
+
+#[derive(Serialize)]
+struct Document<'a, R, T> {
+    schema: u32,
+    kind: &'static str,
+    items: &'a [R],
+    totals: &'a T,
+}
+
+#[derive(Serialize)]
+struct Line<'a, T> {
+    kind: &'static str,
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(items)) => items.iter().map(|v| cell(Some(v))).collect::<Vec<_>>().join(", "),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Writes `items` and `totals` to `out` in `format`.
+pub fn write<R: Record, T: Serialize>(mut out: impl Write, format: Format, items: &[R], totals: &T) -> Result<()> {
+    match format {
+        Format::Json => {
+            let document = Document { schema: SCHEMA, kind: R::KIND, items, totals };
+            serde_json::to_writer_pretty(&mut out, &document)?;
+            writeln!(out)?;
+        }
+        Format::Jsonl => {
+            for item in items {
+                serde_json::to_writer(&mut out, &Line { kind: R::KIND, value: item })?;
+                writeln!(out)?;
+            }
+            serde_json::to_writer(&mut out, &Line { kind: "totals", value: totals })?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(R::COLUMNS)?;
+            for item in items {
+                let value = serde_json::to_value(item)?;
+                writer.write_record(R::COLUMNS.iter().map(|c| cell(value.get(c))))?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct ActivityRecord {
+    pub id: u32,
+    /// Day the activity counts towards, in the configured timezone.
+    pub date: String,
+    pub started_at: Option<String>,
+    pub name: String,
+    pub minutes: u32,
+    pub project: Option<String>,
+    /// Explicit flag or the project's default.
+    pub billable: bool,
+    pub tags: Vec<String>,
+    pub tickets: Vec<String>,
+    pub participants: Vec<String>,
+    /// `manual`, `git`, `worktree` (a draft from `scan-worktree`) or
+    /// `import`. Activities saved without a source count as `manual`.
+    pub source: SourceKind,
+    pub invoice: Option<String>,
+}
+
+impl Record for ActivityRecord {
+    const KIND: &'static str = "activity";
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "date", "started_at", "name", "minutes", "project", "billable",
+        "tags", "tickets", "participants", "source", "invoice",
+    ];
+}
+
+impl ActivityRecord {
+    pub fn new(act: &Activity, config: &Config, calendar: &Calendar) -> Self {
+        Self {
+            id: act.id,
+            date: calendar.day_of(act).map(|d| d.to_string()).unwrap_or_else(|| act.date.clone()),
+            started_at: act.started_at.map(|s| s.to_rfc3339()),
+            name: act.name.clone(),
+            minutes: act.duration_minutes,
+            project: act.project.clone(),
+            billable: config.is_billable(act),
+            tags: act.tags.clone(),
+            tickets: act.tickets.clone(),
+            participants: act.participants.clone(),
+            source: act.source().map(|s| s.kind).unwrap_or_default(),
+            invoice: act.invoice.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ActivityTotals {
+    pub count: usize,
+    pub minutes: u32,
+    pub average_minutes: f64,
+    pub billable_minutes: u32,
+    pub non_billable_minutes: u32,
+}
+
+impl ActivityTotals {
+    pub fn new<'a>(activities: impl IntoIterator<Item = &'a Activity> + Clone, config: &Config) -> Self {
+        let (count, minutes) = activities.clone().into_iter().fold((0, 0), |(n, m), a| (n + 1, m + a.duration_minutes));
+        let (billable_minutes, non_billable_minutes) = billable_split(activities, config);
+        Self {
+            count,
+            minutes,
+            average_minutes: if count == 0 { 0.0 } else { minutes as f64 / count as f64 },
+            billable_minutes,
+            non_billable_minutes,
+        }
+    }
+}
+
+/// Records and totals for `activities`, as `list` writes them.
+pub fn activity_output<'a>(
+    activities: impl IntoIterator<Item = &'a Activity> + Clone,
+    config: &Config,
+    calendar: &Calendar,
+) -> (Vec<ActivityRecord>, ActivityTotals) {
+    let items = activities.clone().into_iter().map(|a| ActivityRecord::new(a, config, calendar)).collect();
+    (items, ActivityTotals::new(activities, config))
+}
+
+#[derive(Serialize)]
+pub struct NamedMinutes {
+    pub name: String,
+    pub minutes: u32,
+}
+
+#[derive(Serialize)]
+pub struct PeriodRecord {
+    pub period: Period,
+    pub start: NaiveDate,
+    /// Last day of the period.
+    pub end: NaiveDate,
+    pub minutes: u32,
+    pub activities: usize,
+    pub active_days: usize,
+    pub average_per_activity: f64,
+    pub average_per_active_day: f64,
+    pub billable_minutes: u32,
+    pub non_billable_minutes: u32,
+    pub top_activities: Vec<NamedMinutes>,
+}
+
+impl Record for PeriodRecord {
+    const KIND: &'static str = "period";
+    const COLUMNS: &'static [&'static str] = &[
+        "period", "start", "end", "minutes", "activities", "active_days",
+        "average_per_activity", "average_per_active_day", "billable_minutes", "non_billable_minutes",
+    ];
+}
+
+impl PeriodRecord {
+    pub fn new(summary: &PeriodSummary, period: Period, activities: &[Activity], config: &Config, calendar: &Calendar) -> Self {
+        let end = calendar.period_end(summary.start, period);
+        let (billable_minutes, non_billable_minutes) = billable_split(
+            activities.iter().filter(|a| calendar.day_of(a).is_some_and(|d| d >= summary.start && d < end)),
+            config,
+        );
+        Self {
+            period,
+            start: summary.start,
+            end: end.pred_opt().unwrap_or(end),
+            minutes: summary.total_minutes,
+            activities: summary.activity_count,
+            active_days: summary.active_days,
+            average_per_activity: summary.average_per_activity(),
+            average_per_active_day: summary.average_per_active_day(),
+            billable_minutes,
+            non_billable_minutes,
+            top_activities: summary.top_activities
+                .iter()
+                .map(|(name, minutes)| NamedMinutes { name: name.clone(), minutes: *minutes })
+                .collect(),
+        }
+    }
+}
+
+/// A record per `period` with activity totals, as `report --period`
+/// writes them.
+pub fn period_output(activities: &[Activity], period: Period, config: &Config, calendar: &Calendar) -> (Vec<PeriodRecord>, ActivityTotals) {
+    let items = summarize_by_period(activities, period, calendar)
+        .iter()
+        .map(|s| PeriodRecord::new(s, period, activities, config, calendar))
+        .collect();
+    (items, ActivityTotals::new(activities, config))
+}
+
+/// Time per ticket or per person.
+#[derive(Serialize)]
+pub struct GroupRecord {
+    /// `ticket` or `person`.
+    pub group: &'static str,
+    pub key: String,
+    pub minutes: u32,
+}
+
+impl Record for GroupRecord {
+    const KIND: &'static str = "group";
+    const COLUMNS: &'static [&'static str] = &["group", "key", "minutes"];
+}
+
+#[derive(Serialize)]
+pub struct GroupTotals {
+    pub minutes: u32,
+    /// Minutes of activities outside every group, e.g. without a ticket.
+    pub ungrouped_minutes: u32,
+    pub billable_minutes: u32,
+    pub non_billable_minutes: u32,
+}
+
+#[derive(Serialize)]
+pub struct WeekRecord {
+    pub week_start: NaiveDate,
+    pub active_days: u32,
+}
+
+impl Record for WeekRecord {
+    const KIND: &'static str = "week";
+    const COLUMNS: &'static [&'static str] = &["week_start", "active_days"];
+}
+
+#[derive(Serialize)]
+pub struct StreakTotals {
+    pub current: u32,
+    pub longest: u32,
+    pub longest_end: Option<NaiveDate>,
+    pub active_days: usize,
+    pub missed_days: Vec<NaiveDate>,
+}
+
+pub fn streak_output(stats: &StreakStats) -> (Vec<WeekRecord>, StreakTotals) {
+    let weeks = stats.weekly
+        .iter()
+        .map(|(week_start, active_days)| WeekRecord { week_start: *week_start, active_days: *active_days })
+        .collect();
+    let totals = StreakTotals {
+        current: stats.current,
+        longest: stats.longest,
+        longest_end: stats.longest_end,
+        active_days: stats.active_days,
+        missed_days: stats.missed_days.clone(),
+    };
+    (weeks, totals)
+}
+
+#[derive(Serialize)]
+pub struct TagRecord {
+    pub tag: String,
+    #[serde(flatten)]
+    pub durations: DurationStats,
+}
+
+impl Record for TagRecord {
+    const KIND: &'static str = "tag";
+    const COLUMNS: &'static [&'static str] = &[
+        "tag", "count", "total", "mean", "median", "p75", "p90", "p95", "std_dev", "min", "max",
+    ];
+}
+
+#[derive(Serialize)]
+pub struct BucketCount {
+    pub bucket: &'static str,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct StatsTotals {
+    #[serde(flatten)]
+    pub durations: DurationStats,
+    pub longest_id: Option<u32>,
+    pub shortest_id: Option<u32>,
+    /// Activity count per duration bucket, shortest first.
+    pub buckets: Vec<BucketCount>,
+    /// Minutes per weekday, from the first day of the week.
+    pub by_weekday: Vec<NamedMinutes>,
+    /// Minutes per hour of the day, 24 entries; empty without start times.
+    pub by_hour: Vec<u32>,
+}
+
+pub fn stats_output(stats: &Analytics) -> (Vec<TagRecord>, StatsTotals) {
+    let tags = stats.by_tag
+        .iter()
+        .map(|t| TagRecord { tag: t.tag.clone(), durations: t.durations.clone() })
+        .collect();
+    let totals = StatsTotals {
+        durations: stats.durations.clone(),
+        longest_id: stats.longest,
+        shortest_id: stats.shortest,
+        buckets: stats.buckets.iter().map(|&(bucket, count)| BucketCount { bucket, count }).collect(),
+        by_weekday: stats.by_weekday
+            .iter()
+            .map(|(day, minutes)| NamedMinutes { name: day.to_string(), minutes: *minutes })
+            .collect(),
+        by_hour: if stats.has_times() { stats.by_hour.to_vec() } else { Vec::new() },
+    };
+    (tags, totals)
+}
+
+#[derive(Serialize)]
+pub struct ChangeRecord {
+    /// `total`, `activities`, `commits`, `project` or `tag`.
+    pub group: &'static str,
+    pub key: String,
+    pub previous: u32,
+    pub current: u32,
+    pub change: i64,
+    /// Relative change in percent; `null` when the previous value is 0.
+    pub percent: Option<f64>,
+}
+
+impl Record for ChangeRecord {
+    const KIND: &'static str = "change";
+    const COLUMNS: &'static [&'static str] = &["group", "key", "previous", "current", "change", "percent"];
+}
+
+impl ChangeRecord {
+    fn new(group: &'static str, key: &str, previous: u32, current: u32) -> Self {
+        Self {
+            group,
+            key: key.to_string(),
+            previous,
+            current,
+            change: current as i64 - previous as i64,
+            percent: percent_change(previous, current),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CompareTotals {
+    pub current_from: NaiveDate,
+    pub current_to: NaiveDate,
+    pub previous_from: NaiveDate,
+    pub previous_to: NaiveDate,
+    pub current_minutes: u32,
+    pub previous_minutes: u32,
+}
+
+pub fn compare_output(comparison: &Comparison) -> (Vec<ChangeRecord>, CompareTotals) {
+    let (before, after) = (&comparison.previous, &comparison.current);
+    let mut rows = vec![
+        ChangeRecord::new("total", "minutes", before.total_minutes, after.total_minutes),
+        ChangeRecord::new("activities", "count", before.activity_count as u32, after.activity_count as u32),
+        ChangeRecord::new("commits", "count", before.commit_count as u32, after.commit_count as u32),
+    ];
+    for (group, changes) in [("project", comparison.projects()), ("tag", comparison.tags())] {
+        rows.extend(changes.into_iter().map(|(key, p, c)| ChangeRecord::new(group, &key, p, c)));
+    }
+    let totals = CompareTotals {
+        current_from: after.range.from,
+        current_to: after.range.to,
+        previous_from: before.range.from,
+        previous_to: before.range.to,
+        current_minutes: after.total_minutes,
+        previous_minutes: before.total_minutes,
+    };
+    (rows, totals)
+}
+
+#[derive(Serialize)]
+pub struct GoalRecord {
+    pub name: String,
+    pub scope: String,
+    pub window: Period,
+    /// `min` for targets, `max` for budgets.
+    pub direction: &'static str,
+    pub target_minutes: u32,
+    pub minutes: u32,
+    pub ratio: f64,
+    pub elapsed: f64,
+    /// `met`, `on_track`, `at_risk` or `over`.
+    pub status: &'static str,
+    pub period_start: NaiveDate,
+    /// Last day of the window.
+    pub period_end: NaiveDate,
+}
+
+impl Record for GoalRecord {
+    const KIND: &'static str = "goal";
+    const COLUMNS: &'static [&'static str] = &[
+        "name", "scope", "window", "direction", "target_minutes", "minutes",
+        "ratio", "elapsed", "status", "period_start", "period_end",
+    ];
+}
+
+impl GoalRecord {
+    pub fn new(progress: &GoalProgress) -> Self {
+        let goal = &progress.goal;
+        Self {
+            name: goal.name.clone(),
+            scope: goal.scope(),
+            window: goal.window,
+            direction: match goal.direction {
+                GoalDirection::Min => "min",
+                GoalDirection::Max => "max",
+            },
+            target_minutes: goal.minutes,
+            minutes: progress.minutes,
+            ratio: progress.ratio(),
+            elapsed: progress.elapsed,
+            status: match progress.status {
+                GoalStatus::Met => "met",
+                GoalStatus::OnTrack => "on_track",
+                GoalStatus::AtRisk => "at_risk",
+                GoalStatus::Over => "over",
+            },
+            period_start: progress.period_start,
+            period_end: progress.period_end.pred_opt().unwrap_or(progress.period_end),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct GoalTotals {
+    pub goals: usize,
+    pub met: usize,
+    pub on_track: usize,
+    pub at_risk: usize,
+    pub over: usize,
+}
+
+impl GoalTotals {
+    pub fn new(records: &[GoalRecord]) -> Self {
+        let count = |status: &str| records.iter().filter(|r| r.status == status).count();
+        Self {
+            goals: records.len(),
+            met: count("met"),
+            on_track: count("on_track"),
+            at_risk: count("at_risk"),
+            over: count("over"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DayRecord {
+    pub date: NaiveDate,
+    pub minutes: u32,
+    /// Intensity from 0 to 4, as shown in the heatmap.
+    pub level: usize,
+}
+
+impl Record for DayRecord {
+    const KIND: &'static str = "day";
+    const COLUMNS: &'static [&'static str] = &["date", "minutes", "level"];
+}
+
+#[derive(Serialize)]
+pub struct HeatmapTotals {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub weeks: usize,
+    pub minutes: u32,
+    pub active_days: usize,
+    pub max_minutes: u32,
+}
+
+pub fn heatmap_output(heatmap: &Heatmap) -> (Vec<DayRecord>, HeatmapTotals) {
+    let days: Vec<DayRecord> = (0..heatmap.weeks)
+        .flat_map(|week| (0..7).filter_map(move |row| heatmap.cell(week, row)))
+        .map(|(date, minutes)| DayRecord { date, minutes, level: heatmap.level(minutes) })
+        .collect();
+    let totals = HeatmapTotals {
+        from: heatmap.start,
+        to: heatmap.today,
+        weeks: heatmap.weeks,
+        minutes: heatmap.total_minutes(),
+        active_days: days.iter().filter(|d| d.minutes > 0).count(),
+        max_minutes: heatmap.max_minutes(),
+    };
+    (days, totals)
+}
+
+#[derive(Serialize)]
+pub struct StandupRecord {
+    pub project: String,
+    pub text: String,
+    pub minutes: u32,
+    pub from_git: bool,
+}
+
+impl Record for StandupRecord {
+    const KIND: &'static str = "standup_item";
+    const COLUMNS: &'static [&'static str] = &["project", "text", "minutes", "from_git"];
+}
+
+#[derive(Serialize)]
+pub struct StandupTotals {
+    pub date: NaiveDate,
+    pub minutes: u32,
+    pub projects: Vec<NamedMinutes>,
+}
+
+pub fn standup_output(standup: &Standup) -> (Vec<StandupRecord>, StandupTotals) {
+    let items = standup.groups
+        .iter()
+        .flat_map(|g| {
+            g.items.iter().map(|item| StandupRecord {
+                project: g.project.clone(),
+                text: item.text.clone(),
+                minutes: item.minutes,
+                from_git: item.from_git,
+            })
+        })
+        .collect();
+    let totals = StandupTotals {
+        date: standup.day,
+        minutes: standup.total_minutes(),
+        projects: standup.groups
+            .iter()
+            .map(|g| NamedMinutes { name: g.project.clone(), minutes: g.minutes })
+            .collect(),
+    };
+    (items, totals)
+}
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+
+use crate::activity::{identity, Activity};
+use crate::calendar::{Calendar, Period};
+use crate::config::Config;
+
+/// Minutes split into `(billable, non_billable)`.
+pub fn billable_split<'a>(activities: impl IntoIterator<Item = &'a Activity>, config: &Config) -> (u32, u32) {
+    activities.into_iter().fold((0, 0), |(billable, internal), act| {
+        if config.is_billable(act) {
+            (billable + act.duration_minutes, internal)
+        } else {
+            (billable, internal + act.duration_minutes)
+        }
+    })
+}
+
+/// Minutes per key, keys being the same when `same` maps them to the same
+/// value. Each group is shown as its first key.
+fn totals_by(
+    activities: &[Activity],
+    keys: impl Fn(&Activity) -> &[String],
+    same: impl Fn(&str) -> String,
+) -> Vec<(String, u32)> {
+    let mut totals: Vec<(String, String, u32)> = Vec::new();
+    for act in activities {
+        let mut counted = Vec::new();
+        for key in keys(act) {
+            let id = same(key);
+            if counted.contains(&id) {
+                continue;
+            }
+            match totals.iter_mut().find(|(k, _, _)| *k == id) {
+                Some((_, _, mins)) => *mins += act.duration_minutes,
+                None => totals.push((id.clone(), key.clone(), act.duration_minutes)),
+            }
+            counted.push(id);
+        }
+    }
+    let mut totals: Vec<(String, u32)> = totals.into_iter().map(|(_, key, mins)| (key, mins)).collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+/// Total minutes per linked ticket, largest first. An activity that
+/// references several tickets counts fully towards each of them.
+pub fn time_by_ticket(activities: &[Activity]) -> Vec<(String, u32)> {
+    totals_by(activities, |a| &a.tickets, str::to_string)
+}
+
+/// Total minutes per participant, largest first. Paired work counts fully
+/// towards every collaborator. People are told apart by [`identity`], so one
+/// email under several names counts once.
+pub fn time_by_person(activities: &[Activity]) -> Vec<(String, u32)> {
+    totals_by(activities, |a| &a.participants, identity)
+}
+
+/// Totals for one day, week or month.
+#[derive(Debug, Clone)]
+pub struct PeriodSummary {
+    pub start: NaiveDate,
+    pub total_minutes: u32,
+    pub activity_count: usize,
+    pub active_days: usize,
+    /// Minutes per activity name, largest first.
+    pub top_activities: Vec<(String, u32)>,
+}
+
+impl PeriodSummary {
+    pub fn average_per_activity(&self) -> f64 {
+        self.total_minutes as f64 / self.activity_count.max(1) as f64
+    }
+
+    pub fn average_per_active_day(&self) -> f64 {
+        self.total_minutes as f64 / self.active_days.max(1) as f64
+    }
+}
+
+/// Groups activities by calendar period, most recent period first.
+/// Activities whose date can't be determined are left out.
+pub fn summarize_by_period(activities: &[Activity], period: Period, calendar: &Calendar) -> Vec<PeriodSummary> {
+    let mut buckets: Vec<(NaiveDate, Vec<(NaiveDate, &Activity)>)> = Vec::new();
+    for act in activities {
+        let Some(day) = calendar.day_of(act) else { continue };
+        let start = calendar.period_start(day, period);
+        match buckets.iter_mut().find(|(s, _)| *s == start) {
+            Some((_, acts)) => acts.push((day, act)),
+            None => buckets.push((start, vec![(day, act)])),
+        }
+    }
+    buckets.sort_by_key(|(start, _)| std::cmp::Reverse(*start));
+
+    buckets
+        .into_iter()
+        .map(|(start, acts)| {
+            let mut days: Vec<NaiveDate> = acts.iter().map(|(d, _)| *d).collect();
+            days.sort();
+            days.dedup();
+            let owned: Vec<Activity> = acts.iter().map(|(_, a)| (*a).clone()).collect();
+            PeriodSummary {
+                start,
+                total_minutes: owned.iter().map(|a| a.duration_minutes).sum(),
+                activity_count: owned.len(),
+                active_days: days.len(),
+                top_activities: totals_by_name(&owned),
+            }
+        })
+        .collect()
+}
+
+/// Total minutes per activity name, largest first.
+pub fn totals_by_name(activities: &[Activity]) -> Vec<(String, u32)> {
+    let mut totals: Vec<(String, u32)> = Vec::new();
+    for act in activities {
+        let name = act.name.lines().next().unwrap_or("").trim();
+        match totals.iter_mut().find(|(n, _)| n == name) {
+            Some((_, mins)) => *mins += act.duration_minutes,
+            None => totals.push((name.to_string(), act.duration_minutes)),
+        }
+    }
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
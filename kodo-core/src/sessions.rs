@@ -0,0 +1,50 @@
+use chrono::{DateTime, Duration, Local};
+
+/// A run of events with no gap longer than the session timeout.
+#[derive(Debug, Clone)]
+pub struct Session<T> {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub items: Vec<T>,
+}
+
+impl<T> Session<T> {
+    /// Length of the session rounded up to whole minutes, at least 1.
+    pub fn minutes(&self) -> u32 {
+        let secs = (self.end - self.start).num_seconds().max(0);
+        std::cmp::max(((secs + 59) / 60) as u32, 1)
+    }
+}
+
+/// Moves the start of the first session back to `since` when that is
+/// earlier on the same day. Used when events only show when work ended,
+/// such as file modification times, and `since` is when it likely began.
+pub fn start_first_at<T>(sessions: &mut [Session<T>], since: DateTime<Local>) {
+    if let Some(first) = sessions.first_mut()
+        && since < first.start
+        && since.date_naive() == first.start.date_naive()
+    {
+        first.start = since;
+    }
+}
+
+/// Groups `(start, end, item)` events into sessions. A new session begins
+/// whenever an event starts more than `timeout` after the previous one ended.
+pub fn group_sessions<T>(
+    mut events: Vec<(DateTime<Local>, DateTime<Local>, T)>,
+    timeout: Duration,
+) -> Vec<Session<T>> {
+    events.sort_by_key(|(start, _, _)| *start);
+
+    let mut sessions: Vec<Session<T>> = Vec::new();
+    for (start, end, item) in events {
+        match sessions.last_mut() {
+            Some(current) if start - current.end <= timeout => {
+                current.end = current.end.max(end);
+                current.items.push(item);
+            }
+            _ => sessions.push(Session { start, end, items: vec![item] }),
+        }
+    }
+    sessions
+}
@@ -0,0 +1,131 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::timesheet::{format_hours, NO_PROJECT};
+
+/// Layout used when no template is given.
+pub const DEFAULT_TEMPLATE: &str = "**{weekday} {date}** ({total})\n\n{projects}";
+
+/// The last weekday before `today`; on a Monday that is the previous Friday.
+pub fn previous_working_day(today: NaiveDate) -> NaiveDate {
+    let mut day = today - Days::new(1);
+    while matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+        day = day - Days::new(1);
+    }
+    day
+}
+
+#[derive(Debug, Clone)]
+pub struct StandupItem {
+    pub text: String,
+    pub minutes: u32,
+    pub from_git: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct StandupGroup {
+    pub project: String,
+    pub minutes: u32,
+    pub items: Vec<StandupItem>,
+}
+
+/// What was done on one day, grouped by project.
+#[derive(Debug, Clone)]
+pub struct Standup {
+    pub day: NaiveDate,
+    /// Largest project first.
+    pub groups: Vec<StandupGroup>,
+}
+
+impl Standup {
+    /// Collects manual and commit activities on `day`. Activities with the
+    /// same first line are merged into one item.
+    pub fn build(activities: &[Activity], day: NaiveDate, calendar: &Calendar) -> Self {
+        let mut groups: Vec<StandupGroup> = Vec::new();
+        for act in activities.iter().filter(|a| calendar.day_of(a) == Some(day)) {
+            let project = act.project().unwrap_or(NO_PROJECT);
+            let text = act.name.lines().next().unwrap_or("").trim().to_string();
+            let group = match groups.iter().position(|g| g.project == project) {
+                Some(i) => &mut groups[i],
+                None => {
+                    groups.push(StandupGroup { project: project.to_string(), minutes: 0, items: Vec::new() });
+                    groups.last_mut().unwrap()
+                }
+            };
+            group.minutes += act.duration_minutes;
+            match group.items.iter_mut().find(|i| i.text == text) {
+                Some(item) => item.minutes += act.duration_minutes,
+                None => group.items.push(StandupItem { text, minutes: act.duration_minutes, from_git: act.is_from_git() }),
+            }
+        }
+        groups.sort_by(|a, b| b.minutes.cmp(&a.minutes).then_with(|| a.project.cmp(&b.project)));
+
+        Self { day, groups }
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.groups.iter().map(|g| g.minutes).sum()
+    }
+
+    /// The per-project bullet lists used for `{projects}`.
+    pub fn projects_markdown(&self) -> String {
+        if self.groups.is_empty() {
+            return "_Nothing logged._".to_string();
+        }
+        let sections: Vec<String> = self.groups
+            .iter()
+            .map(|group| {
+                let mut out = format!("*{}* ({})\n", group.project, format_hours(group.minutes));
+                for item in &group.items {
+                    let kind = if item.from_git { "commit, " } else { "" };
+                    out.push_str(&format!("- {} ({}{})\n", item.text, kind, format_hours(item.minutes)));
+                }
+                out
+            })
+            .collect();
+        sections.join("\n").trim_end().to_string()
+    }
+
+    /// Fills in a template. Placeholders: `{date}`, `{weekday}`, `{total}`,
+    /// `{projects}` (bullets grouped by project) and `{items}` (one flat
+    /// bullet list). The template is read once, so braces in activity names
+    /// are never taken for placeholders; unknown ones are kept as written.
+    pub fn render(&self, template: &str) -> String {
+        let value = |name: &str| match name {
+            "date" => Some(self.day.to_string()),
+            "weekday" => Some(self.day.format("%A").to_string()),
+            "total" => Some(format_hours(self.total_minutes())),
+            "items" => {
+                let items: String = self.groups
+                    .iter()
+                    .flat_map(|g| &g.items)
+                    .map(|i| format!("- {}\n", i.text))
+                    .collect();
+                Some(items.trim_end().to_string())
+            }
+            "projects" => Some(self.projects_markdown()),
+            _ => None,
+        };
+
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let placeholder = rest.find('}').and_then(|end| Some((value(&rest[1..end])?, end)));
+            match placeholder {
+                Some((text, end)) => {
+                    out.push_str(&text);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
@@ -0,0 +1,72 @@
+use chrono::{Days, NaiveDate};
+use std::collections::BTreeSet;
+
+use crate::activity::Activity;
+use crate::calendar::{Calendar, Period};
+
+/// How consistently activities were logged, day by day.
+#[derive(Debug, Clone, Default)]
+pub struct StreakStats {
+    /// Consecutive active days up to today. A streak that ended yesterday
+    /// still counts, since today isn't over yet.
+    pub current: u32,
+    pub longest: u32,
+    /// Last day of the longest streak.
+    pub longest_end: Option<NaiveDate>,
+    pub active_days: usize,
+    /// Days without any activity between the first active day and today.
+    pub missed_days: Vec<NaiveDate>,
+    /// Active days per week, oldest week first, keyed by the week's first day.
+    pub weekly: Vec<(NaiveDate, u32)>,
+}
+
+impl StreakStats {
+    /// Computes streaks from manual and commit-derived activities alike.
+    pub fn compute(activities: &[Activity], calendar: &Calendar, today: NaiveDate) -> Self {
+        let days: BTreeSet<NaiveDate> = activities
+            .iter()
+            .filter(|a| a.duration_minutes > 0)
+            .filter_map(|a| calendar.day_of(a))
+            .filter(|d| *d <= today)
+            .collect();
+        let Some(first) = days.first().copied() else {
+            return Self::default();
+        };
+
+        let mut stats = Self { active_days: days.len(), ..Self::default() };
+        let mut run = 0;
+        let mut day = first;
+        while day <= today {
+            if days.contains(&day) {
+                run += 1;
+                if run > stats.longest {
+                    stats.longest = run;
+                    stats.longest_end = Some(day);
+                }
+            } else {
+                run = 0;
+                if day != today {
+                    stats.missed_days.push(day);
+                }
+            }
+            day = day + Days::new(1);
+        }
+
+        let yesterday = today - Days::new(1);
+        let mut day = if days.contains(&today) { today } else { yesterday };
+        while days.contains(&day) {
+            stats.current += 1;
+            day = day - Days::new(1);
+        }
+
+        let mut week = calendar.period_start(first, Period::Week);
+        while week <= today {
+            let end = calendar.period_end(week, Period::Week);
+            let active = days.range(week..end).count() as u32;
+            stats.weekly.push((week, active));
+            week = end;
+        }
+
+        stats
+    }
+}
@@ -0,0 +1,44 @@
+use regex::Regex;
+use anyhow::{Result, Context};
+
+/// Finds issue and ticket references (`#123`, `JIRA-456`, ...) in free text.
+#[derive(Debug, Clone)]
+pub struct TicketMatcher {
+    patterns: Vec<Regex>,
+    exclude: Vec<String>,
+}
+
+impl TicketMatcher {
+    /// Matches `patterns`, skipping ids such as `UTF-8` whose part before
+    /// the last `-` is one of the `exclude` prefixes.
+    pub fn new(patterns: &[String], exclude: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid ticket pattern {:?}", p)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { patterns, exclude: exclude.to_vec() })
+    }
+
+    fn is_excluded(&self, id: &str) -> bool {
+        id.rsplit_once('-').is_some_and(|(prefix, number)| {
+            self.exclude.iter().any(|e| e == prefix) && number.chars().all(|c| c.is_ascii_digit())
+        })
+    }
+
+    /// Returns every distinct ticket id in `text`, in order of first
+    /// appearance, leaving out excluded ones.
+    pub fn extract(&self, text: &str) -> Vec<String> {
+        let mut found: Vec<(usize, String)> = Vec::new();
+        for re in &self.patterns {
+            for caps in re.captures_iter(text) {
+                let m = caps.get(1).or_else(|| caps.get(0)).unwrap();
+                let id = m.as_str().to_string();
+                if !self.is_excluded(&id) && !found.iter().any(|(_, t)| *t == id) {
+                    found.push((m.start(), id));
+                }
+            }
+        }
+        found.sort_by_key(|(pos, _)| *pos);
+        found.into_iter().map(|(_, t)| t).collect()
+    }
+}
@@ -0,0 +1,239 @@
+use chrono::NaiveDate;
+use serde::{Serialize, Deserialize};
+use anyhow::Result;
+
+use crate::activity::Activity;
+use crate::config::Config;
+use crate::html::escape;
+
+/// Project name used for activities that don't belong to one.
+pub const NO_PROJECT: &str = "(no project)";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    #[default]
+    Up,
+    Down,
+    Nearest,
+}
+
+/// How billable durations are rounded, e.g. up to the next 15 minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rounding {
+    /// Step in minutes; 1 means no rounding.
+    pub increment: u32,
+    pub mode: RoundingMode,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self { increment: 1, mode: RoundingMode::Up }
+    }
+}
+
+impl Rounding {
+    pub fn apply(&self, minutes: u32) -> u32 {
+        let step = self.increment.max(1);
+        let down = minutes / step * step;
+        match self.mode {
+            RoundingMode::Down => down,
+            RoundingMode::Up if down == minutes => minutes,
+            RoundingMode::Up => down + step,
+            RoundingMode::Nearest if (minutes - down) * 2 >= step => down + step,
+            RoundingMode::Nearest => down,
+        }
+    }
+}
+
+/// Time spent on one project on one day.
+#[derive(Debug, Clone)]
+pub struct TimesheetLine {
+    pub date: NaiveDate,
+    pub project: String,
+    /// Sum of the rounded durations of the activities on this line.
+    pub minutes: u32,
+    /// The billable part of `minutes`.
+    pub billable_minutes: u32,
+    pub descriptions: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Timesheet {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub project: Option<String>,
+    /// Sorted by date, then project.
+    pub lines: Vec<TimesheetLine>,
+}
+
+/// Formats minutes as `h:mm`.
+pub fn format_hours(minutes: u32) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn decimal_hours(minutes: u32) -> String {
+    format!("{:.2}", minutes as f64 / 60.0)
+}
+
+impl Timesheet {
+    /// Collects activities between `from` and `to` (inclusive), optionally
+    /// for a single project. Each activity is rounded before it is added to
+    /// its day and project, using the rounding and billable settings in
+    /// `config`.
+    pub fn build(
+        activities: &[Activity],
+        from: NaiveDate,
+        to: NaiveDate,
+        project: Option<&str>,
+        config: &Config,
+    ) -> Result<Self> {
+        let calendar = config.calendar()?;
+        let mut lines: Vec<TimesheetLine> = Vec::new();
+        for act in activities {
+            let Some(day) = calendar.day_of(act) else { continue };
+            if day < from || day > to {
+                continue;
+            }
+            let act_project = act.project.as_deref().unwrap_or(NO_PROJECT);
+            if project.is_some_and(|p| !p.eq_ignore_ascii_case(act_project)) {
+                continue;
+            }
+
+            let minutes = config.rounding.apply(act.duration_minutes);
+            let billable_minutes = if config.is_billable(act) { minutes } else { 0 };
+            let description = act.name.lines().next().unwrap_or("").trim().to_string();
+            match lines.iter_mut().find(|l| l.date == day && l.project == act_project) {
+                Some(line) => {
+                    line.minutes += minutes;
+                    line.billable_minutes += billable_minutes;
+                    if !line.descriptions.contains(&description) {
+                        line.descriptions.push(description);
+                    }
+                }
+                None => lines.push(TimesheetLine {
+                    date: day,
+                    project: act_project.to_string(),
+                    minutes,
+                    billable_minutes,
+                    descriptions: vec![description],
+                }),
+            }
+        }
+        lines.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.project.cmp(&b.project)));
+
+        Ok(Self { from, to, project: project.map(str::to_string), lines })
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.lines.iter().map(|l| l.minutes).sum()
+    }
+
+    pub fn billable_minutes(&self) -> u32 {
+        self.lines.iter().map(|l| l.billable_minutes).sum()
+    }
+
+    /// Total minutes per day, in date order.
+    pub fn day_totals(&self) -> Vec<(NaiveDate, u32)> {
+        let mut totals: Vec<(NaiveDate, u32)> = Vec::new();
+        for line in &self.lines {
+            match totals.last_mut() {
+                Some((day, mins)) if *day == line.date => *mins += line.minutes,
+                _ => totals.push((line.date, line.minutes)),
+            }
+        }
+        totals
+    }
+
+    fn title(&self) -> String {
+        match &self.project {
+            Some(p) => format!("Timesheet: {} ({} to {})", p, self.from, self.to),
+            None => format!("Timesheet ({} to {})", self.from, self.to),
+        }
+    }
+
+    pub fn to_csv(&self) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(["date", "project", "minutes", "hours", "billable_minutes", "description"])?;
+        for line in &self.lines {
+            writer.write_record([
+                line.date.to_string(),
+                line.project.clone(),
+                line.minutes.to_string(),
+                decimal_hours(line.minutes),
+                line.billable_minutes.to_string(),
+                line.descriptions.join("; "),
+            ])?;
+        }
+        let total = self.total_minutes();
+        let billable = self.billable_minutes().to_string();
+        writer.write_record(["total", "", &total.to_string(), &decimal_hours(total), &billable, ""])?;
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title());
+        out.push_str("| Date | Project | Hours | Description |\n");
+        out.push_str("| ---- | ------- | ----: | ----------- |\n");
+        for (day, day_total) in self.day_totals() {
+            for line in self.lines.iter().filter(|l| l.date == day) {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    line.date,
+                    line.project.replace('|', "\\|"),
+                    format_hours(line.minutes),
+                    line.descriptions.join("; ").replace('|', "\\|"),
+                ));
+            }
+            out.push_str(&format!("| | **Subtotal {}** | **{}** | |\n", day, format_hours(day_total)));
+        }
+        out.push_str(&format!("\n**Grand total: {}** ({} h)\n", format_hours(self.total_minutes()), decimal_hours(self.total_minutes())));
+        out.push_str(&format!(
+            "\nBillable: {} | Non-billable: {}\n",
+            format_hours(self.billable_minutes()),
+            format_hours(self.total_minutes() - self.billable_minutes()),
+        ));
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut rows = String::new();
+        for (day, day_total) in self.day_totals() {
+            for line in self.lines.iter().filter(|l| l.date == day) {
+                rows.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                    line.date,
+                    escape(&line.project),
+                    format_hours(line.minutes),
+                    escape(&line.descriptions.join("; ")),
+                ));
+            }
+            rows.push_str(&format!(
+                "<tr class=\"subtotal\"><td></td><td>Subtotal {}</td><td class=\"num\">{}</td><td></td></tr>\n",
+                day,
+                format_hours(day_total),
+            ));
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+             body {{ font-family: sans-serif; margin: 2em; }}\n\
+             table {{ border-collapse: collapse; width: 100%; }}\n\
+             th, td {{ border-bottom: 1px solid #ccc; padding: 4px 8px; text-align: left; }}\n\
+             .num {{ text-align: right; }}\n\
+             .subtotal td {{ font-weight: bold; background: #f4f4f4; }}\n\
+             .total {{ font-size: 1.2em; font-weight: bold; margin-top: 1em; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+             <table>\n<tr><th>Date</th><th>Project</th><th class=\"num\">Hours</th><th>Description</th></tr>\n\
+             {rows}</table>\n<p class=\"total\">Grand total: {total} ({decimal} h)</p>\n\
+             <p>Billable: {billable} | Non-billable: {internal}</p>\n</body>\n</html>\n",
+            title = escape(&self.title()),
+            rows = rows,
+            total = format_hours(self.total_minutes()),
+            decimal = decimal_hours(self.total_minutes()),
+            billable = format_hours(self.billable_minutes()),
+            internal = format_hours(self.total_minutes() - self.billable_minutes()),
+        )
+    }
+}
@@ -0,0 +1,217 @@
+//! Jira worklogs. Activities that reference Jira issues are turned into
+//! worklog payloads and queued in a file of pending uploads, which a
+//! separate step sends through a [`WorklogSink`] such as Jira's REST API:
+//!
+//! ```text
+//! POST {url}/rest/api/2/issue/ABC-123/worklog
+//! {"started": "2026-10-15T09:00:00.000+0000", "timeSpentSeconds": 5400, "comment": "Fix the parser"}
+//! ```
+
+use anyhow::{Result, Context};
+use chrono::{NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+use std::path::Path;
+
+use crate::activity::Activity;
+use crate::calendar::Calendar;
+use crate::formats::scheduled;
+
+/// Environment variable holding the Jira API token.
+pub const TOKEN_VAR: &str = "KODO_JIRA_TOKEN";
+
+/// Where worklogs are sent, stored under `jira` in the config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JiraConfig {
+    /// Base URL of the Jira site, e.g. `https://acme.atlassian.net`.
+    pub url: Option<String>,
+    /// Account email. Jira Cloud needs it alongside an API token; without
+    /// it the token is sent as a personal access token.
+    pub email: Option<String>,
+}
+
+/// A worklog as Jira's REST API takes it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Worklog {
+    /// Start time, e.g. `2026-10-15T09:00:00.000+0200`.
+    pub started: String,
+    pub time_spent_seconds: u32,
+    pub comment: String,
+}
+
+/// A worklog waiting to be sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingUpload {
+    pub activity_id: u32,
+    pub issue: String,
+    pub worklog: Worklog,
+    /// Why the last attempt to send it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// A worklog Jira has accepted, kept so it isn't queued again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadedWorklog {
+    pub activity_id: u32,
+    pub issue: String,
+    /// Id Jira gave the worklog.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worklog_id: Option<String>,
+    pub uploaded_at: String,
+}
+
+/// Where queued worklogs are sent, e.g. a Jira site.
+pub trait WorklogSink {
+    /// Adds a worklog to `issue` and returns the id it was given.
+    fn add_worklog(&self, issue: &str, worklog: &Worklog) -> Result<Option<String>>;
+}
+
+/// Whether `ticket` looks like a Jira issue key such as `ABC-123`.
+pub fn is_issue_key(ticket: &str) -> bool {
+    let Some((project, number)) = ticket.rsplit_once('-') else { return false };
+    project.starts_with(|c: char| c.is_ascii_uppercase())
+        && project.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Worklogs for activities between `from` and `to` that reference Jira
+/// issues, oldest first. An activity that references several issues has its
+/// time split between them, so nothing is logged twice. Activities without
+/// a start time follow one another from 09:00 on their day.
+pub fn worklogs(
+    activities: &[Activity],
+    from: NaiveDate,
+    to: NaiveDate,
+    project: Option<&str>,
+    calendar: &Calendar,
+) -> Vec<PendingUpload> {
+    let mut entries: Vec<_> = scheduled(activities, calendar)
+        .into_iter()
+        .filter(|(act, _)| calendar.day_of(act).is_some_and(|d| d >= from && d <= to))
+        .filter(|(act, _)| project.is_none_or(|p| act.project().is_some_and(|ap| ap.eq_ignore_ascii_case(p))))
+        .collect();
+    entries.sort_by_key(|(act, start)| (*start, act.id));
+
+    let mut uploads = Vec::new();
+    for (act, start) in entries {
+        let issues: Vec<&String> = act.tickets.iter().filter(|t| is_issue_key(t)).collect();
+        if issues.is_empty() {
+            continue;
+        }
+        // Whole minutes each, the remainder going to the first issue
+        let share = act.duration_minutes / issues.len() as u32;
+        let remainder = act.duration_minutes % issues.len() as u32;
+        for (i, issue) in issues.into_iter().enumerate() {
+            let minutes = if i == 0 { share + remainder } else { share };
+            if minutes == 0 {
+                continue;
+            }
+            uploads.push(PendingUpload {
+                activity_id: act.id,
+                issue: issue.clone(),
+                worklog: Worklog {
+                    started: start.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string(),
+                    time_spent_seconds: minutes * 60,
+                    comment: act.name.trim().to_string(),
+                },
+                last_error: None,
+            });
+        }
+    }
+    uploads
+}
+
+/// Worklogs waiting to be sent and those already sent, stored as JSON next
+/// to the activities file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorklogQueue {
+    pub pending: Vec<PendingUpload>,
+    pub uploaded: Vec<UploadedWorklog>,
+}
+
+/// Outcome of sending the pending worklogs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubmitSummary {
+    pub uploaded: usize,
+    /// Issue and error of each worklog that is still pending.
+    pub failed: Vec<(String, String)>,
+}
+
+impl WorklogQueue {
+    pub fn load_from_file(path: &Path) -> Result<WorklogQueue> {
+        if !path.exists() {
+            return Ok(WorklogQueue::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read worklog queue {:?}", path))?;
+        if contents.trim().is_empty() {
+            return Ok(WorklogQueue::default());
+        }
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse JSON in {:?}", path))
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Whether the activity's time on `issue` is already pending or sent.
+    pub fn contains(&self, activity_id: u32, issue: &str) -> bool {
+        self.pending.iter().any(|p| p.activity_id == activity_id && p.issue == issue)
+            || self.uploaded.iter().any(|u| u.activity_id == activity_id && u.issue == issue)
+    }
+
+    /// Queues the uploads that aren't pending or sent yet and returns how
+    /// many were added.
+    pub fn add(&mut self, uploads: Vec<PendingUpload>) -> usize {
+        let before = self.pending.len();
+        for upload in uploads {
+            if !self.contains(upload.activity_id, &upload.issue) {
+                self.pending.push(upload);
+            }
+        }
+        self.pending.len() - before
+    }
+
+    /// Sends every pending worklog. Accepted ones move to `uploaded` and
+    /// `on_upload` is called with the queue right away, so it can be saved
+    /// before the next request; an error from it stops the submission. The
+    /// rest stay pending with the reason they failed.
+    pub fn submit(
+        &mut self,
+        sink: &impl WorklogSink,
+        mut on_upload: impl FnMut(&WorklogQueue) -> Result<()>,
+    ) -> Result<SubmitSummary> {
+        let mut summary = SubmitSummary::default();
+        let mut i = 0;
+        while i < self.pending.len() {
+            let upload = &mut self.pending[i];
+            match sink.add_worklog(&upload.issue, &upload.worklog) {
+                Ok(worklog_id) => {
+                    let upload = self.pending.remove(i);
+                    summary.uploaded += 1;
+                    self.uploaded.push(UploadedWorklog {
+                        activity_id: upload.activity_id,
+                        issue: upload.issue,
+                        worklog_id,
+                        uploaded_at: Utc::now().to_rfc3339(),
+                    });
+                    on_upload(self)?;
+                }
+                Err(e) => {
+                    let error = format!("{:#}", e);
+                    summary.failed.push((upload.issue.clone(), error.clone()));
+                    upload.last_error = Some(error);
+                    i += 1;
+                }
+            }
+        }
+        Ok(summary)
+    }
+}
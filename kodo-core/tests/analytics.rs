@@ -0,0 +1,46 @@
+use chrono::{DateTime, Weekday};
+use kodo_core::analytics::{percentile, Analytics, DurationStats};
+use kodo_core::Calendar;
+
+mod common;
+use common::{act, Build};
+
+#[test]
+fn duration_stats() {
+    let stats = DurationStats::compute(&[10, 20, 30, 40, 100]);
+    assert_eq!(stats.count, 5);
+    assert_eq!(stats.total, 200);
+    assert_eq!(stats.mean, 40.0);
+    assert_eq!(stats.median, 30.0);
+    assert_eq!(stats.p90, 76.0);
+    assert_eq!((stats.std_dev * 100.0).round(), 3162.0);
+    assert_eq!((stats.min, stats.max), (10, 100));
+
+    assert_eq!(percentile(&[], 50.0), 0.0);
+    assert_eq!(percentile(&[7], 90.0), 7.0);
+    assert_eq!(DurationStats::compute(&[]), DurationStats::default());
+}
+
+#[test]
+fn distributions() {
+    let mut timed = act("task 4", 90, "2026-10-14").with_id(4);
+    timed.started_at = Some(DateTime::parse_from_rfc3339("2026-10-14T09:30:00+00:00").unwrap());
+    let acts = vec![
+        act("task 1", 10, "2026-10-12").with_id(1).with_tags(&["review"]),
+        act("task 2", 45, "2026-10-12").with_id(2).with_tags(&["deep-work", "review"]),
+        act("task 3", 300, "2026-10-13").with_id(3).with_tags(&["deep-work"]),
+        timed,
+    ];
+    let calendar = Calendar::new("monday", "UTC").unwrap();
+
+    let stats = Analytics::compute(&acts, &calendar);
+    assert_eq!(stats.longest, Some(3));
+    assert_eq!(stats.shortest, Some(1));
+    assert_eq!(stats.buckets, vec![("<15m", 1), ("15-30m", 0), ("30-60m", 1), ("1-2h", 1), ("2-4h", 0), ("4h+", 1)]);
+    assert_eq!(stats.by_weekday[0], (Weekday::Mon, 55));
+    assert_eq!(stats.by_weekday[2], (Weekday::Wed, 90));
+    assert_eq!((stats.by_hour[9], stats.by_hour[10], stats.by_hour[11]), (30, 60, 0));
+
+    let tags: Vec<(&str, u32)> = stats.by_tag.iter().map(|t| (t.tag.as_str(), t.durations.total)).collect();
+    assert_eq!(tags, vec![("deep-work", 345), ("review", 55)]);
+}
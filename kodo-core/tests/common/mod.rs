@@ -0,0 +1,61 @@
+//! Fixtures shared by the integration tests. Each test file uses only some
+//! of them.
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use kodo_core::{Activity, Calendar};
+
+pub fn day(d: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()
+}
+
+/// A Monday-first calendar in UTC, so results don't depend on the machine.
+pub fn utc() -> Calendar {
+    Calendar::new("monday", "UTC").unwrap()
+}
+
+/// An activity of `minutes` on `date`; the `with_*` methods of [`Build`]
+/// fill in the rest.
+pub fn act(name: &str, minutes: u32, date: &str) -> Activity {
+    Activity {
+        name: name.to_string(),
+        duration_minutes: minutes,
+        date: date.to_string(),
+        ..Default::default()
+    }
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+/// Builder-style setters for test activities.
+pub trait Build {
+    fn with_id(self, id: u32) -> Self;
+    fn with_project(self, project: &str) -> Self;
+    fn with_tags(self, tags: &[&str]) -> Self;
+    fn with_tickets(self, tickets: &[&str]) -> Self;
+    fn with_participants(self, people: &[&str]) -> Self;
+}
+
+impl Build for Activity {
+    fn with_id(self, id: u32) -> Self {
+        Activity { id, ..self }
+    }
+
+    fn with_project(self, project: &str) -> Self {
+        Activity { project: Some(project.to_string()), ..self }
+    }
+
+    fn with_tags(self, tags: &[&str]) -> Self {
+        Activity { tags: strings(tags), ..self }
+    }
+
+    fn with_tickets(self, tickets: &[&str]) -> Self {
+        Activity { tickets: strings(tickets), ..self }
+    }
+
+    fn with_participants(self, people: &[&str]) -> Self {
+        Activity { participants: strings(people), ..self }
+    }
+}
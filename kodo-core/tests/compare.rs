@@ -0,0 +1,50 @@
+use kodo_core::compare::{percent_change, Comparison, DateRange};
+use kodo_core::{Calendar, Source};
+
+mod common;
+use common::{act, day, Build};
+
+#[test]
+fn parses_named_and_custom_ranges() {
+    let calendar = Calendar::default();
+    let today = day("2026-10-15");
+    let range = |spec| DateRange::parse(spec, &calendar, today).unwrap();
+
+    assert_eq!(range("week"), DateRange { from: day("2026-10-12"), to: day("2026-10-18") });
+    assert_eq!(range("last-week"), DateRange { from: day("2026-10-05"), to: day("2026-10-11") });
+    assert_eq!(range("last-month"), DateRange { from: day("2026-09-01"), to: day("2026-09-30") });
+    assert_eq!(range("2026-10-01..2026-10-10").previous(), DateRange { from: day("2026-09-21"), to: day("2026-09-30") });
+    assert_eq!(range("month").previous(), range("last-month"));
+    assert!(DateRange::parse("2026-10-10..2026-10-01", &calendar, today).is_err());
+    assert!(DateRange::parse("fortnight", &calendar, today).is_err());
+}
+
+#[test]
+fn compares_totals_projects_and_tags() {
+    let mut commit = act("work", 30, "2026-10-13").with_project("acme");
+    commit.source = Some(Source::git("/repo", "abc", "Ana"));
+    let mut draft = act("work", 15, "2026-10-14").with_project("acme");
+    draft.source = Some(Source::worktree("/repo"));
+    let acts = vec![
+        act("work", 120, "2026-10-06").with_project("acme").with_tags(&["meetings"]),
+        act("work", 60, "2026-10-07").with_project("side"),
+        act("work", 90, "2026-10-12").with_project("acme").with_tags(&["meetings"]),
+        commit,
+        draft,
+    ];
+    let calendar = Calendar::default();
+    let this = DateRange { from: day("2026-10-12"), to: day("2026-10-18") };
+
+    let comparison = Comparison::build(&acts, this, this.previous(), &calendar);
+    assert_eq!(comparison.previous.total_minutes, 180);
+    assert_eq!(comparison.current.total_minutes, 135);
+    assert_eq!(comparison.current.commit_count, 1);
+    assert_eq!(comparison.projects(), vec![
+        ("acme".to_string(), 120, 135),
+        ("side".to_string(), 60, 0),
+    ]);
+    assert_eq!(comparison.tags(), vec![("meetings".to_string(), 120, 90)]);
+
+    assert_eq!(percent_change(180, 120).map(f64::round), Some(-33.0));
+    assert_eq!(percent_change(0, 120), None);
+}
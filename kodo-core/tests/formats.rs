@@ -0,0 +1,369 @@
+use kodo_core::formats::csv::{self, CsvOptions, Mapping};
+use kodo_core::formats::{parse_duration, ImportReport, ImportStatus};
+use kodo_core::{Activity, Calendar, Config};
+
+mod common;
+use common::{act, utc, Build};
+
+#[test]
+fn parses_durations() {
+    assert_eq!(parse_duration("90", false), Ok(90));
+    assert_eq!(parse_duration("1.5", true), Ok(90));
+    assert_eq!(parse_duration("1:30", false), Ok(90));
+    assert_eq!(parse_duration("01:30:40", false), Ok(91));
+    assert_eq!(parse_duration("1h 30m", false), Ok(90));
+    assert_eq!(parse_duration("2.5h", false), Ok(150));
+    assert_eq!(parse_duration("45min", false), Ok(45));
+    assert!(parse_duration("soon", false).is_err());
+    assert!(parse_duration("", false).is_err());
+    assert_eq!(parse_duration("99999999:00", false), Err("duration too large".to_string()));
+    assert_eq!(parse_duration("4294967295:59:59", false), Err("duration too large".to_string()));
+    assert_eq!(parse_duration("1e12", true), Err("duration too large".to_string()));
+    assert_eq!(parse_duration("9999999999h", false), Err("duration too large".to_string()));
+}
+
+#[test]
+fn csv_import_detects_header_and_reports_rows() {
+    let text = "Description,Date,Start,End,Project,Tags\n\
+                Write docs,2026-10-15,09:00,10:30,acme,\"docs, writing\"\n\
+                ,2026-10-15,11:00,11:30,acme,\n\
+                Review,2026-10-16,14:00,13:00,acme,\n";
+    let rows = csv::import(text, &CsvOptions::default(), &utc()).unwrap();
+    assert_eq!(rows.len(), 3);
+
+    let first = rows[0].result.as_ref().unwrap();
+    assert_eq!(first.duration_minutes, 90);
+    assert_eq!(first.date, "2026-10-15");
+    assert_eq!(first.started_at.unwrap().to_rfc3339(), "2026-10-15T09:00:00+00:00");
+    assert_eq!(first.tags, vec!["docs", "writing"]);
+    assert_eq!(rows[1].result.as_ref().unwrap_err(), "missing name");
+    assert_eq!(rows[2].line, 4);
+    assert_eq!(rows[2].result.as_ref().unwrap_err(), "ends before it starts");
+}
+
+#[test]
+fn csv_mapping_without_header() {
+    let options = CsvOptions {
+        mappings: vec!["name=2".parse().unwrap(), "hours=3".parse().unwrap(), "date=1".parse().unwrap()],
+        has_header: Some(false),
+        delimiter: Some(b';'),
+    };
+    let rows = csv::import("2026-10-15;Planning;1.25\n", &options, &utc()).unwrap();
+    assert_eq!(rows[0].result.as_ref().unwrap().duration_minutes, 75);
+    assert!("colour=3".parse::<Mapping>().is_err());
+}
+
+#[test]
+fn export_round_trips_and_flags_duplicates() {
+    let existing = vec![act("Write docs", 90, "2026-10-15").with_id(7).with_project("acme").with_tags(&["docs"])];
+    let config = Config { timezone: "UTC".to_string(), ..Default::default() };
+    let text = csv::export(&existing, &config).unwrap();
+    let mut rows = csv::import(&text, &CsvOptions::default(), &utc()).unwrap();
+    assert_eq!(rows[0].result.as_ref().unwrap().tags, vec!["docs"]);
+
+    rows.extend(csv::import("name,date,minutes\nNew,2026-10-16,30\nNew,2026-10-16,30\n", &CsvOptions::default(), &utc()).unwrap());
+    let report = ImportReport::build(&existing, rows, &utc());
+    let statuses: Vec<&ImportStatus> = report.entries.iter().map(|e| &e.status).collect();
+    assert_eq!(statuses, vec![
+        &ImportStatus::Duplicate("matches existing activity #7".to_string()),
+        &ImportStatus::Added,
+        &ImportStatus::Duplicate("repeats line 2".to_string()),
+    ]);
+    assert_eq!(report.added().next().unwrap().id, 8);
+    assert_eq!(report.counts(), (1, 2, 0));
+}
+
+#[test]
+fn toggl_report_round_trip() {
+    let text = "User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags,Amount (USD)\n\
+                Ana,ana@example.com,ACME,Website,,Fix header,Yes,2026-10-15,23:30:00,2026-10-16,00:45:00,01:15:00,\"frontend, bug\",\n\
+                Ana,ana@example.com,,Internal,,Planning,No,2026-10-16,09:00:00,2026-10-16,09:30:00,00:30:00,,\n\
+                Ana,ana@example.com,,Internal,,Runaway timer,No,2026-10-16,10:00:00,2026-10-16,10:30:00,99999999:00:00,,\n";
+    let mut rows = kodo_core::formats::toggl::import(text, &utc()).unwrap();
+    assert_eq!(rows.pop().unwrap().result.unwrap_err(), "duration too large");
+    let first = rows[0].result.as_ref().unwrap();
+    assert_eq!((first.name.as_str(), first.duration_minutes), ("Fix header", 75));
+    assert_eq!(first.project.as_deref(), Some("Website"));
+    assert_eq!(first.tags, vec!["frontend", "bug"]);
+    assert_eq!(first.billable, Some(true));
+    assert_eq!(rows[1].result.as_ref().unwrap().billable, Some(false));
+
+    let acts: Vec<Activity> = rows.into_iter().map(|r| r.result.unwrap()).collect();
+    let config = Config { timezone: "UTC".to_string(), ..Default::default() };
+    let exported = kodo_core::formats::toggl::export(&acts, &config).unwrap();
+    assert!(exported.contains("Website,,Fix header,Yes,2026-10-15,23:30:00,2026-10-16,00:45:00,01:15:00,\"frontend, bug\""));
+    let again = kodo_core::formats::toggl::import(&exported, &utc()).unwrap();
+    assert_eq!(again[0].result.as_ref().unwrap().started_at, acts[0].started_at);
+
+    let untimed = [act("Review", 30, "2026-10-16"), act("Planning", 45, "2026-10-16")];
+    let exported = kodo_core::formats::toggl::export(&untimed, &config).unwrap();
+    assert!(exported.contains(",Review,Yes,2026-10-16,09:00:00,2026-10-16,09:30:00,00:30:00,"));
+    assert!(exported.contains(",Planning,Yes,2026-10-16,09:30:00,2026-10-16,10:15:00,00:45:00,"));
+}
+
+#[test]
+fn clockify_report_import_and_export() {
+    let text = "Project,Client,Description,Task,User,Group,Email,Tags,Billable,Start Date,Start Time,End Date,End Time,Duration (h),Duration (decimal)\n\
+                Website,ACME,Review,,Ana,,,review,Yes,10/15/2026,02:00:00 PM,10/15/2026,03:30:00 PM,01:30:00,1.50\n";
+    let rows = kodo_core::formats::clockify::import(text, &utc()).unwrap();
+    let act = rows[0].result.as_ref().unwrap();
+    assert_eq!(act.started_at.unwrap().to_rfc3339(), "2026-10-15T14:00:00+00:00");
+    assert_eq!(act.duration_minutes, 90);
+
+    let config = Config { timezone: "UTC".to_string(), ..Default::default() };
+    let exported = kodo_core::formats::clockify::export(std::slice::from_ref(act), &config).unwrap();
+    assert!(exported.ends_with("Website,,Review,,,,,review,Yes,10/15/2026,02:00:00 PM,10/15/2026,03:30:00 PM,01:30:00,1.50\n"));
+    assert!(kodo_core::formats::clockify::import("Name,Date\nx,y\n", &utc()).is_err());
+}
+
+#[test]
+fn timewarrior_intervals_round_trip() {
+    use chrono::{DateTime, Utc};
+    use kodo_core::formats::timewarrior;
+
+    let now: DateTime<Utc> = "2026-10-16T10:00:00Z".parse().unwrap();
+    let text = "inc 20261015T090000Z - 20261015T103000Z # code \"deep work\" project:acme billable # \"Fix \\\"parser\\\"\"\n\
+                \n\
+                inc 20261016T080000Z # standup\n\
+                inc 20261016T070000Z - 20261016T071500Z # # \"Notes only\"\n\
+                inc garbage\n";
+    let rows = timewarrior::import(text, Some("2026-10.data"), now, &utc());
+    assert_eq!(rows.len(), 4);
+
+    let first = rows[0].result.as_ref().unwrap();
+    assert_eq!(first.name, "Fix \"parser\"");
+    assert_eq!(first.duration_minutes, 90);
+    assert_eq!(first.tags, vec!["code", "deep work"]);
+    assert_eq!(first.project.as_deref(), Some("acme"));
+    assert_eq!(first.billable, Some(true));
+
+    // Open intervals run until now; without an annotation the tags name it
+    let open = rows[1].result.as_ref().unwrap();
+    assert_eq!((open.name.as_str(), open.duration_minutes), ("standup", 120));
+    assert_eq!(rows[1].line, 3);
+    assert!(rows[2].result.as_ref().unwrap().tags.is_empty());
+    assert!(rows[3].result.is_err());
+
+    let acts: Vec<Activity> = rows.into_iter().filter_map(|r| r.result.ok()).collect();
+    let files = timewarrior::export(&acts, &utc());
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].0, "2026-10.data");
+    assert!(files[0].1.starts_with(
+        "inc 20261015T090000Z - 20261015T103000Z # code \"deep work\" project:acme billable # \"Fix \\\"parser\\\"\"\n"
+    ));
+
+    let again = timewarrior::import(&files[0].1, None, now, &utc());
+    let report = ImportReport::build(&acts, again, &utc());
+    assert_eq!(report.counts(), (0, 3, 0));
+}
+
+#[test]
+fn ical_export_lays_out_untimed_activities() {
+    use chrono::{DateTime, Utc};
+    use kodo_core::formats::ical;
+
+    let now: DateTime<Utc> = "2026-10-16T10:00:00Z".parse().unwrap();
+    let acts = vec![
+        act("Review, then merge", 30, "2026-10-15").with_id(1),
+        act("Write the quarterly planning document for the infrastructure team\nwith notes", 60, "2026-10-15")
+            .with_id(2)
+            .with_tags(&["docs", "planning"])
+            .with_project("acme"),
+    ];
+    let text = ical::export(&acts, &utc(), now);
+    assert!(text.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(text.ends_with("END:VCALENDAR\r\n"));
+    assert!(text.lines().all(|l| l.len() <= 75));
+    assert!(text.contains("DTSTART:20261015T090000Z\r\nDTEND:20261015T093000Z\r\nSUMMARY:Review\\, then merge\r\n"));
+    assert!(text.contains("DTSTART:20261015T093000Z\r\nDTEND:20261015T103000Z\r\n"));
+    assert!(text.contains("CATEGORIES:docs,planning\r\n"));
+    assert!(text.contains("X-KODO-PROJECT:acme\r\n"));
+
+    // Folded lines unfold on import, and the export imports back unchanged
+    let rows = ical::import(&text, &ical::IcsFilter::default(), &utc());
+    let report = ImportReport::build(&acts, rows, &utc());
+    assert_eq!(report.counts(), (0, 2, 0));
+}
+
+#[test]
+fn ical_import_filters_and_rejects_events() {
+    use kodo_core::formats::ical::{self, IcsFilter};
+
+    let text = "BEGIN:VCALENDAR\r\n\
+                BEGIN:VEVENT\r\n\
+                SUMMARY:Sprint planning\r\n\
+                DTSTART;TZID=\"Europe/Berlin\":20261015T100000\r\n\
+                DTEND;TZID=Europe/Berlin:20261015T110000\r\n\
+                CATEGORIES:Meeting,Work\r\n\
+                BEGIN:VALARM\r\n\
+                SUMMARY:Reminder\r\n\
+                END:VALARM\r\n\
+                END:VEVENT\r\n\
+                BEGIN:VEVENT\r\n\
+                SUMMARY:Client call\r\n\
+                DESCRIPTION:Discuss the acme\r\n  invoice\r\n\
+                DTSTART:20261016T140000Z\r\n\
+                DURATION:PT45M\r\n\
+                END:VEVENT\r\n\
+                BEGIN:VEVENT\r\n\
+                SUMMARY:Dentist\r\n\
+                DTSTART:20261016T080000Z\r\n\
+                DURATION:PT1H\r\n\
+                END:VEVENT\r\n\
+                BEGIN:VEVENT\r\n\
+                SUMMARY:Offsite\r\n\
+                DTSTART;VALUE=DATE:20261017\r\n\
+                CATEGORIES:work\r\n\
+                END:VEVENT\r\n\
+                BEGIN:VEVENT\r\n\
+                SUMMARY:Retro\r\n\
+                STATUS:CANCELLED\r\n\
+                DTSTART:20261016T150000Z\r\n\
+                DTEND:20261016T160000Z\r\n\
+                CATEGORIES:meeting\r\n\
+                END:VEVENT\r\n\
+                END:VCALENDAR\r\n";
+    let calendar = Calendar::new("monday", "+02:00").unwrap();
+
+    let all = ical::import(text, &IcsFilter::default(), &calendar);
+    assert_eq!(all.len(), 5);
+
+    let filter = IcsFilter { keywords: vec!["ACME".into()], categories: vec!["work".into(), "meeting".into()] };
+    let rows = ical::import(text, &filter, &calendar);
+    assert_eq!(rows.len(), 4);
+
+    let planning = rows[0].result.as_ref().unwrap();
+    assert_eq!(rows[0].line, 2);
+    assert_eq!(planning.duration_minutes, 60);
+    assert_eq!(planning.started_at.unwrap().to_rfc3339(), "2026-10-15T10:00:00+02:00");
+    assert_eq!(planning.tags, vec!["Meeting", "Work"]);
+
+    let call = rows[1].result.as_ref().unwrap();
+    assert_eq!((call.name.as_str(), call.duration_minutes), ("Client call", 45));
+    assert_eq!(call.date, "2026-10-16");
+
+    assert_eq!(rows[2].result.as_ref().unwrap_err(), "all-day event");
+    assert_eq!(rows[3].result.as_ref().unwrap_err(), "event was cancelled");
+}
+
+#[test]
+fn org_clock_entries_round_trip() {
+    use kodo_core::formats::org;
+
+    let text = "#+TITLE: Work log\n\
+                * Acme :client:\n\
+                :PROPERTIES:\n\
+                :PROJECT: acme\n\
+                :END:\n\
+                ** DONE [#A] Fix the parser :code:\n\
+                :LOGBOOK:\n\
+                CLOCK: [2026-10-15 Thu 09:00]--[2026-10-15 Thu 10:30] =>  1:30\n\
+                CLOCK: [2026-10-16 10:00]--[2026-10-16 10:20] =>  0:20\n\
+                :END:\n\
+                * Reading\n\
+                CLOCK: [2026-10-16 Fri 14:00]\n\
+                CLOCK: [2026-10-16 Fri 15:00]--[2026-10-16 Fri 14:00] => -1:00\n";
+    let rows = org::import(text, &utc());
+    assert_eq!(rows.len(), 4);
+
+    let first = rows[0].result.as_ref().unwrap();
+    assert_eq!(rows[0].line, 8);
+    assert_eq!((first.name.as_str(), first.duration_minutes), ("Fix the parser", 90));
+    assert_eq!(first.project.as_deref(), Some("acme"));
+    assert_eq!(first.tags, vec!["client", "code"]);
+    assert_eq!(rows[1].result.as_ref().unwrap().date, "2026-10-16");
+    assert_eq!(rows[2].result.as_ref().unwrap_err(), "clock is still running");
+    assert!(rows[3].result.is_err());
+
+    let mut acts: Vec<Activity> = rows.into_iter().filter_map(|r| r.result.ok()).collect();
+    acts.push(act("Planning", 30, "2026-10-16").with_id(9));
+    let out = org::export(&acts, &utc());
+    assert!(out.starts_with(
+        "* [2026-10-15 Thu] (1:30)\n\
+         ** Fix the parser :client:code:\n\
+         :PROPERTIES:\n\
+         :PROJECT:  acme\n\
+         :END:\n\
+         :LOGBOOK:\n\
+         CLOCK: [2026-10-15 Thu 09:00]--[2026-10-15 Thu 10:30] =>  1:30\n\
+         :END:\n\
+         * [2026-10-16 Fri] (0:50)\n"
+    ));
+    assert!(out.contains("** Planning\n:LOGBOOK:\nCLOCK: [2026-10-16 Fri 09:00]--[2026-10-16 Fri 09:30] =>  0:30\n"));
+
+    let report = ImportReport::build(&acts, org::import(&out, &utc()), &utc());
+    assert_eq!(report.counts(), (0, 3, 0));
+}
+
+#[test]
+fn markdown_daily_notes() {
+    use kodo_core::formats::markdown;
+
+    let acts = vec![
+        act("Review PRs", 30, "2026-10-15").with_id(1),
+        Activity {
+            started_at: Some("2026-10-15T09:00:00+00:00".parse().unwrap()),
+            ..act("Fix the parser\nhandles CRLF now", 90, "2026-10-15").with_id(2).with_project("acme").with_tags(&["deep work"])
+        },
+        act("Standup", 15, "2026-10-16").with_id(3),
+    ];
+    assert_eq!(
+        markdown::export(&acts, &utc()),
+        "## 2026-10-15 Thursday\n\n\
+         - 09:00-10:30 Fix the parser (1:30) *acme* #deep-work\n  handles CRLF now\n\
+         - Review PRs (0:30)\n\n\
+         **Total: 2:00**\n\n\
+         ## 2026-10-16 Friday\n\n\
+         - Standup (0:15)\n\n\
+         **Total: 0:15**\n"
+    );
+}
+
+#[test]
+fn heartbeats_group_into_sessions_per_project() {
+    use chrono::Duration;
+    use kodo_core::formats::heartbeats;
+
+    let text = r#"[
+        {"timestamp": "2026-10-15T09:00:00Z", "entity": "/src/kodo/main.rs", "project": "kodo"},
+        {"timestamp": "2026-10-15T09:10:00Z", "entity": "/src/kodo/lib.rs", "project": "kodo", "duration": 120},
+        {"timestamp": "2026-10-15T09:05:00Z", "entity": "/src/acme/app.py", "project": "acme"},
+        {"timestamp": 1792062000, "entity": "/src/kodo/main.rs", "project": "kodo"},
+        {"entity": "/src/kodo/main.rs", "project": "kodo"}
+    ]"#;
+    let rows = heartbeats::import(text, Duration::minutes(15), &utc()).unwrap();
+    assert_eq!(rows.len(), 4);
+
+    let first = rows[0].result.as_ref().unwrap();
+    assert_eq!(first.name, "lib.rs, main.rs");
+    assert_eq!(first.project.as_deref(), Some("kodo"));
+    assert_eq!(first.duration_minutes, 12);
+    assert_eq!(first.started_at.unwrap().to_rfc3339(), "2026-10-15T09:00:00+00:00");
+
+    // Unix seconds, and a lone heartbeat still counts as a minute
+    let later = rows[1].result.as_ref().unwrap();
+    assert_eq!(rows[1].line, 3);
+    assert_eq!(later.project.as_deref(), Some("acme"));
+    assert_eq!(rows[2].result.as_ref().unwrap().started_at.unwrap().to_rfc3339(), "2026-10-15T11:00:00+00:00");
+    assert_eq!(rows[3].result.as_ref().unwrap_err(), "missing timestamp");
+
+    // A longer timeout joins the sessions
+    let rows = heartbeats::import(text, Duration::hours(2), &utc()).unwrap();
+    assert_eq!(rows.iter().filter(|r| r.result.is_ok()).count(), 2);
+}
+
+#[test]
+fn heartbeats_from_activitywatch_export() {
+    use chrono::Duration;
+    use kodo_core::formats::heartbeats;
+
+    let text = r#"{"buckets": {"aw-watcher-vscode_host": {"events": [
+        {"timestamp": "2026-10-15T10:20:00.000000+00:00", "duration": 300.0, "data": {"file": "/w/b.rs", "project": "/w"}},
+        {"timestamp": "2026-10-15T10:00:00.000000+00:00", "duration": 600.0, "data": {"file": "/w/a.rs", "project": "/w"}}
+    ]}}}"#;
+    let rows = heartbeats::import(text, Duration::minutes(15), &utc()).unwrap();
+    assert_eq!(rows.len(), 1);
+    let act = rows[0].result.as_ref().unwrap();
+    assert_eq!((act.name.as_str(), act.duration_minutes), ("b.rs, a.rs", 25));
+    assert!(heartbeats::import(r#"{"other": 1}"#, Duration::minutes(15), &utc()).is_err());
+}
@@ -0,0 +1,54 @@
+use chrono::NaiveDateTime;
+use kodo_core::goals::{Goal, GoalDirection, GoalProgress, GoalStatus};
+use kodo_core::{Activity, Calendar, Period};
+
+mod common;
+use common::{act, Build};
+
+fn tagged(date: &str, minutes: u32, tag: &str) -> Activity {
+    act("work", minutes, date).with_tags(&[tag])
+}
+
+fn goal(tag: Option<&str>, minutes: u32, direction: GoalDirection) -> Goal {
+    Goal {
+        name: "goal".to_string(),
+        tag: tag.map(str::to_string),
+        project: None,
+        window: Period::Week,
+        minutes,
+        direction,
+    }
+}
+
+#[test]
+fn weekly_minimum_counts_only_matching_tag_in_this_week() {
+    // Thursday noon: half of the Monday-based week has passed.
+    let now = NaiveDateTime::parse_from_str("2026-10-15 12:00", "%Y-%m-%d %H:%M").unwrap();
+    let acts = vec![
+        tagged("2026-10-12", 120, "deep-work"),
+        tagged("2026-10-14", 60, "Deep-Work"),
+        tagged("2026-10-14", 90, "meetings"),
+        tagged("2026-10-09", 300, "deep-work"),
+    ];
+
+    let progress = GoalProgress::compute(&goal(Some("deep-work"), 600, GoalDirection::Min), &acts, &Calendar::default(), now);
+    assert_eq!(progress.minutes, 180);
+    assert_eq!(progress.elapsed, 0.5);
+    assert_eq!(progress.status, GoalStatus::AtRisk);
+
+    let total = GoalProgress::compute(&goal(None, 240, GoalDirection::Min), &acts, &Calendar::default(), now);
+    assert_eq!(total.minutes, 270);
+    assert_eq!(total.status, GoalStatus::Met);
+}
+
+#[test]
+fn maximum_budget_status() {
+    let now = NaiveDateTime::parse_from_str("2026-10-15 12:00", "%Y-%m-%d %H:%M").unwrap();
+    let acts = vec![tagged("2026-10-13", 90, "meetings")];
+    let calendar = Calendar::default();
+
+    let status = |minutes| GoalProgress::compute(&goal(Some("meetings"), minutes, GoalDirection::Max), &acts, &calendar, now).status;
+    assert_eq!(status(300), GoalStatus::OnTrack);
+    assert_eq!(status(120), GoalStatus::AtRisk);
+    assert_eq!(status(60), GoalStatus::Over);
+}
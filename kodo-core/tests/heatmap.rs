@@ -0,0 +1,45 @@
+use kodo_core::heatmap::{Heatmap, MAX_WEEKS};
+use kodo_core::Calendar;
+
+mod common;
+use common::{act, day};
+
+#[test]
+fn places_days_in_week_columns() {
+    let acts = vec![
+        act("work", 30, "2026-09-28"), // Monday of the first week
+        act("work", 45, "2026-10-14"),
+        act("work", 15, "2026-10-14"),
+        act("work", 60, "2026-09-27"), // before the first week
+        act("work", 60, "2026-10-16"), // after today
+    ];
+    let calendar = Calendar::new("monday", "UTC").unwrap();
+    let heatmap = Heatmap::build(&acts, &calendar, day("2026-10-15"), 3);
+
+    assert_eq!(heatmap.start, day("2026-09-28"));
+    assert_eq!(heatmap.minutes.len(), 21);
+    assert_eq!(heatmap.cell(0, 0), Some((day("2026-09-28"), 30)));
+    assert_eq!(heatmap.cell(2, 2), Some((day("2026-10-14"), 60)));
+    assert_eq!(heatmap.cell(2, 3), Some((day("2026-10-15"), 0)));
+    assert_eq!(heatmap.cell(2, 4), None);
+    assert_eq!(heatmap.total_minutes(), 90);
+    assert_eq!(heatmap.max_minutes(), 60);
+}
+
+#[test]
+fn levels_are_relative_to_the_busiest_day() {
+    let calendar = Calendar::new("monday", "UTC").unwrap();
+    let heatmap = Heatmap::build(&[act("work", 100, "2026-10-14")], &calendar, day("2026-10-15"), 1);
+    let levels: Vec<usize> = [0, 1, 25, 26, 50, 51, 75, 76, 100].iter().map(|m| heatmap.level(*m)).collect();
+    assert_eq!(levels, vec![0, 1, 1, 2, 2, 3, 3, 4, 4]);
+
+    let empty = Heatmap::build(&[], &calendar, day("2026-10-15"), 1);
+    assert_eq!(empty.level(30), 0);
+}
+
+#[test]
+fn clamps_the_number_of_weeks() {
+    let calendar = Calendar::new("monday", "UTC").unwrap();
+    assert_eq!(Heatmap::build(&[], &calendar, day("2026-10-15"), 0).weeks, 1);
+    assert_eq!(Heatmap::build(&[], &calendar, day("2026-10-15"), 100_000_000).weeks, MAX_WEEKS);
+}
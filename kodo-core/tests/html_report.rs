@@ -0,0 +1,36 @@
+use kodo_core::compare::DateRange;
+use kodo_core::html_report::HtmlReport;
+use kodo_core::{Config, Source};
+
+mod common;
+use common::{act, day, Build};
+
+#[test]
+fn summarizes_range_with_charts_and_commits() {
+    let mut commit = act("Fix <parser>", 30, "2026-10-13").with_project("acme");
+    commit.source = Some(Source::git("/src/acme", "abc", "Ana"));
+    let acts = vec![
+        act("Before the range", 45, "2026-10-11").with_project("acme"),
+        act("Planning & review", 90, "2026-10-12").with_project("acme"),
+        act("Reading", 60, "2026-10-14"),
+        commit,
+    ];
+    let config = Config { timezone: "UTC".to_string(), ..Default::default() };
+    let range = DateRange { from: day("2026-10-12"), to: day("2026-10-18") };
+
+    let report = HtmlReport::build(&acts, range, &config).unwrap();
+    assert_eq!(report.total_minutes(), 180);
+    assert_eq!(report.daily.len(), 7);
+    assert_eq!(report.daily[1], (day("2026-10-13"), 30));
+    assert_eq!(report.projects[0], ("acme".to_string(), 120));
+    assert_eq!(report.commits.len(), 1);
+    assert_eq!((report.commits[0].commits, report.commits[0].minutes), (1, 30));
+
+    let html = report.to_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert_eq!(html.matches("<svg").count(), 3);
+    assert!(html.contains("Planning &amp; review"));
+    assert!(html.contains("Fix &lt;parser&gt;"));
+    assert!(!html.contains("Before the range"));
+    assert!(!html.contains("<script"));
+}
@@ -0,0 +1,120 @@
+use kodo_core::invoice::{format_cents, Invoice, InvoiceLedger, ProjectConfig};
+use kodo_core::report::billable_split;
+use kodo_core::timesheet::{Rounding, RoundingMode};
+use kodo_core::{Activity, Config};
+
+mod common;
+use common::{act, day, utc, Build};
+
+/// Bills `acme` at 95.50 EUR an hour in quarter hours; `internal` time isn't
+/// billable.
+fn config() -> Config {
+    let mut config = Config {
+        rounding: Rounding { increment: 15, mode: RoundingMode::Up },
+        ..Default::default()
+    };
+    let acme = ProjectConfig { rate: Some(95.5), currency: Some("EUR".to_string()), ..Default::default() };
+    config.projects.insert("acme".to_string(), acme);
+    config.projects.insert("internal".to_string(), ProjectConfig { rate: Some(50.0), billable: Some(false), ..Default::default() });
+    config
+}
+
+fn invoice(number: &str, activities: &[Activity], project: &str) -> Invoice {
+    Invoice::build(number, activities, project, day("2026-10-01"), day("2026-10-31"), &config(), &utc()).unwrap()
+}
+
+fn october(number: &str, activities: &[Activity]) -> Invoice {
+    invoice(number, activities, "acme")
+}
+
+#[test]
+fn bills_rate_times_rounded_minutes() {
+    let acts = vec![
+        act("Task 1", 50, "2026-10-02").with_id(1).with_project("acme"),
+        act("Task 2", 20, "2026-10-01").with_id(2).with_project("ACME"),
+        act("Task 3", 7, "2026-10-03").with_id(3).with_project("acme"),
+        act("Task 4", 60, "2026-09-30").with_id(4).with_project("acme"),
+        act("Task 5", 60, "2026-10-02").with_id(5).with_project("other"),
+    ];
+    let invoice = october("INV-0001", &acts);
+
+    let lines: Vec<(u32, u32, i64)> = invoice.items.iter().map(|i| (i.activity_id, i.minutes, i.amount_cents)).collect();
+    // 95.50 an hour: 30 min is 47.75, 60 min 95.50 and 15 min 23.875, rounded to 23.88
+    assert_eq!(lines, vec![(2, 30, 4775), (1, 60, 9550), (3, 15, 2388)]);
+    assert_eq!(invoice.total_minutes(), 105);
+    assert_eq!(invoice.total_cents(), 16713);
+    assert_eq!(format_cents(invoice.total_cents()), "167.13");
+    assert!(invoice.to_markdown().contains("| | **Total** | **1:45** | **167.13 EUR** |"));
+    assert_eq!(invoice.record().total_cents, 16713);
+}
+
+#[test]
+fn numbers_invoices_sequentially_across_saves() {
+    let path = std::env::temp_dir().join(format!("kodo-invoices-{}.json", std::process::id()));
+    let mut acts = vec![act("Task 1", 60, "2026-10-02").with_id(1).with_project("acme"), act("Task 2", 30, "2026-10-05").with_id(2).with_project("acme")];
+
+    let mut ledger = InvoiceLedger::load_from_file(&path).unwrap();
+    assert_eq!(ledger.next_number(), "INV-0001");
+    let first = october(&ledger.next_number(), &acts[..1]);
+    ledger.issue(&first, &mut acts);
+    ledger.save_to_file(&path).unwrap();
+
+    let mut ledger = InvoiceLedger::load_from_file(&path).unwrap();
+    assert_eq!(ledger.next_number(), "INV-0002");
+    let second = october(&ledger.next_number(), &acts);
+    ledger.issue(&second, &mut acts);
+    ledger.save_to_file(&path).unwrap();
+
+    let ledger = InvoiceLedger::load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(ledger.next_number(), "INV-0003");
+    assert_eq!(ledger.invoices[1].activity_ids, vec![2]);
+}
+
+#[test]
+fn never_bills_an_activity_twice() {
+    let mut acts = vec![act("Task 1", 60, "2026-10-02").with_id(1).with_project("acme"), act("Task 2", 30, "2026-10-05").with_id(2).with_project("acme")];
+    acts[1].invoice = Some("INV-0001".to_string());
+
+    let invoice = october("INV-0002", &acts);
+    assert_eq!(invoice.items.len(), 1);
+    assert_eq!(invoice.items[0].activity_id, 1);
+
+    // A dry run builds the invoice without issuing it, so nothing is stamped
+    assert_eq!(acts[0].invoice, None);
+    assert_eq!(october("INV-0002", &acts).items.len(), 1);
+
+    let mut ledger = InvoiceLedger::default();
+    ledger.issue(&invoice, &mut acts);
+    assert_eq!(acts[0].invoice.as_deref(), Some("INV-0002"));
+    assert!(october("INV-0003", &acts).items.is_empty());
+}
+
+#[test]
+fn refuses_a_project_without_a_rate() {
+    let acts = vec![act("Task 1", 60, "2026-10-02").with_id(1).with_project("acme")];
+    let err = Invoice::build("INV-0001", &acts, "acme", day("2026-10-01"), day("2026-10-31"), &Config::default(), &utc())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "No hourly rate configured for project \"acme\"");
+}
+
+#[test]
+fn leaves_non_billable_time_off() {
+    let mut acts = vec![
+        act("Task 1", 60, "2026-10-02").with_id(1).with_project("acme"),
+        act("Task 2", 30, "2026-10-02").with_id(2).with_project("acme"),
+        act("Task 3", 45, "2026-10-03").with_id(3).with_project("internal"),
+        act("Task 4", 20, "2026-10-03").with_id(4).with_project("Internal"),
+    ];
+    acts[1].billable = Some(false);
+    // An explicit flag wins over the project's default
+    acts[3].billable = Some(true);
+
+    let ids = |invoice: Invoice| invoice.items.iter().map(|i| i.activity_id).collect::<Vec<_>>();
+    assert_eq!(ids(october("INV-0001", &acts)), vec![1]);
+    assert_eq!(ids(invoice("INV-0001", &acts, "internal")), vec![4]);
+
+    assert_eq!(billable_split(&acts, &config()), (80, 75));
+    assert_eq!(billable_split(&acts[..1], &config()), (60, 0));
+    assert_eq!(billable_split(&[act("Task 5", 15, "2026-10-03").with_id(5).with_project("unknown")], &config()), (15, 0));
+}
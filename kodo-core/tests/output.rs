@@ -0,0 +1,148 @@
+use kodo_core::analytics::Analytics;
+use kodo_core::invoice::ProjectConfig;
+use kodo_core::output::{activity_output, period_output, stats_output, write, Format, Record};
+use kodo_core::{Activity, Config, Period, Source};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+mod common;
+use common::{act, Build};
+
+fn fixture() -> (Vec<Activity>, Config) {
+    let mut acts = vec![
+        act("Fix login", 60, "2026-10-12").with_id(1).with_project("acme").with_tags(&["dev"]),
+        act("Standup", 15, "2026-10-13").with_id(2).with_project("internal").with_tags(&["dev"]),
+        act("Fix login", 45, "2026-10-19").with_id(3).with_project("acme").with_tags(&["dev"]),
+    ];
+    acts[0].tickets = vec!["ABC-1".to_string()];
+    acts[2].tags.push("review".to_string());
+    acts[1].source = Some(Source::import("toggl"));
+    let mut config = Config { timezone: "UTC".to_string(), ..Default::default() };
+    config.projects.insert("internal".to_string(), ProjectConfig { billable: Some(false), ..Default::default() });
+    (acts, config)
+}
+
+fn render<R: Record, T: Serialize>(format: Format, items: &[R], totals: &T) -> String {
+    let mut out = Vec::new();
+    write(&mut out, format, items, totals).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn keys(value: &Value) -> Vec<&str> {
+    let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort();
+    keys
+}
+
+fn sorted(fields: &[&'static str]) -> Vec<&'static str> {
+    let mut fields = fields.to_vec();
+    fields.sort();
+    fields
+}
+
+/// Checks the shape every format shares and returns the JSON document.
+fn check_formats<R: Record, T: Serialize>(kind: &str, items: &[R], totals: &T) -> Value {
+    let document: Value = serde_json::from_str(&render(Format::Json, items, totals)).unwrap();
+    assert_eq!(document["schema"], 1);
+    assert_eq!(document["kind"], kind);
+    assert_eq!(document["items"].as_array().unwrap().len(), items.len());
+
+    let lines: Vec<Value> = render(Format::Jsonl, items, totals)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), items.len() + 1);
+    for (line, item) in lines.iter().zip(document["items"].as_array().unwrap()) {
+        assert_eq!(line["kind"], kind);
+        let mut untagged = line.clone();
+        untagged.as_object_mut().unwrap().remove("kind");
+        assert_eq!(&untagged, item);
+    }
+    let mut totals_line = lines.last().unwrap().clone();
+    assert_eq!(totals_line.as_object_mut().unwrap().remove("kind").unwrap(), "totals");
+    assert_eq!(totals_line, document["totals"]);
+
+    let csv = render(Format::Csv, items, totals);
+    let mut rows = csv.lines();
+    assert_eq!(rows.next().unwrap(), R::COLUMNS.join(","));
+    assert_eq!(rows.count(), items.len());
+
+    document
+}
+
+#[test]
+fn list_schema() {
+    let (acts, config) = fixture();
+    let (items, totals) = activity_output(&acts, &config, &config.calendar().unwrap());
+    let document = check_formats("activity", &items, &totals);
+
+    let first = &document["items"][0];
+    assert_eq!(keys(first), sorted(&[
+        "id", "date", "started_at", "name", "minutes", "project", "billable",
+        "tags", "tickets", "participants", "source", "invoice",
+    ]));
+    assert_eq!(first["source"], "manual");
+    assert_eq!(document["items"][1]["source"], "import");
+    assert_eq!(document["items"][1]["billable"], false);
+    assert_eq!(document["totals"], json!({
+        "count": 3,
+        "minutes": 120,
+        "average_minutes": 40.0,
+        "billable_minutes": 105,
+        "non_billable_minutes": 15,
+    }));
+
+    let csv = render(Format::Csv, &items, &totals);
+    assert_eq!(csv.lines().nth(3).unwrap(), "3,2026-10-19,,Fix login,45,acme,true,\"dev, review\",,,manual,");
+}
+
+#[test]
+fn report_period_schema() {
+    let (acts, config) = fixture();
+    let (items, totals) = period_output(&acts, Period::Week, &config, &config.calendar().unwrap());
+    let document = check_formats("period", &items, &totals);
+
+    let weeks = document["items"].as_array().unwrap();
+    assert_eq!(keys(&weeks[0]), sorted(&[
+        "period", "start", "end", "minutes", "activities", "active_days", "average_per_activity",
+        "average_per_active_day", "billable_minutes", "non_billable_minutes", "top_activities",
+    ]));
+    let summary: Vec<(&str, &str, u64, u64)> = weeks
+        .iter()
+        .map(|w| (
+            w["start"].as_str().unwrap(),
+            w["end"].as_str().unwrap(),
+            w["minutes"].as_u64().unwrap(),
+            w["non_billable_minutes"].as_u64().unwrap(),
+        ))
+        .collect();
+    assert_eq!(summary, vec![("2026-10-19", "2026-10-25", 45, 0), ("2026-10-12", "2026-10-18", 75, 15)]);
+    assert_eq!(weeks[1]["top_activities"][0], json!({"name": "Fix login", "minutes": 60}));
+    assert_eq!(document["totals"]["minutes"], 120);
+
+    // Nested lists have no CSV column
+    let csv = render(Format::Csv, &items, &totals);
+    assert!(!csv.lines().next().unwrap().contains("top_activities"));
+}
+
+#[test]
+fn stats_schema() {
+    let (acts, config) = fixture();
+    let (tags, totals) = stats_output(&Analytics::compute(&acts, &config.calendar().unwrap()));
+    let document = check_formats("tag", &tags, &totals);
+
+    let dev = &document["items"][0];
+    assert_eq!(keys(dev), sorted(&[
+        "tag", "count", "total", "mean", "median", "p75", "p90", "p95", "std_dev", "min", "max",
+    ]));
+    assert_eq!((dev["tag"].as_str(), dev["count"].as_u64(), dev["total"].as_u64()), (Some("dev"), Some(3), Some(120)));
+
+    let totals = &document["totals"];
+    assert_eq!(keys(totals), sorted(&[
+        "count", "total", "mean", "median", "p75", "p90", "p95", "std_dev", "min", "max",
+        "longest_id", "shortest_id", "buckets", "by_weekday", "by_hour",
+    ]));
+    assert_eq!((totals["total"].as_u64(), totals["longest_id"].as_u64(), totals["shortest_id"].as_u64()), (Some(120), Some(1), Some(2)));
+    assert_eq!(totals["by_weekday"][0], json!({"name": "Mon", "minutes": 105}));
+    assert_eq!(totals["by_hour"], json!([]));
+}
@@ -0,0 +1,59 @@
+use kodo_core::activity::identity;
+use kodo_core::report::{summarize_by_period, time_by_person};
+use kodo_core::{Activity, Calendar, Period};
+
+mod common;
+use common::{act, Build};
+
+#[test]
+fn weekly_summary_respects_week_start() {
+    let acts = vec![
+        act("Fix login", 60, "2026-10-10"), // Saturday
+        act("Review", 30, "2026-10-11"),    // Sunday
+        act("Fix login", 45, "2026-10-12"), // Monday
+    ];
+
+    let monday = Calendar::new("monday", "local").unwrap();
+    let weeks = summarize_by_period(&acts, Period::Week, &monday);
+    assert_eq!(weeks.len(), 2);
+    assert_eq!(weeks[0].start.to_string(), "2026-10-12");
+    assert_eq!(weeks[1].total_minutes, 90);
+
+    let sunday = Calendar::new("sun", "local").unwrap();
+    let weeks = summarize_by_period(&acts, Period::Week, &sunday);
+    assert_eq!(weeks[0].start.to_string(), "2026-10-11");
+    assert_eq!(weeks[0].total_minutes, 75);
+    assert_eq!(weeks[0].top_activities[0], ("Fix login".to_string(), 45));
+}
+
+fn paired(minutes: u32, people: &[&str]) -> Activity {
+    act("Pairing", minutes, "2026-10-12").with_participants(people)
+}
+
+#[test]
+fn people_are_identified_by_email() {
+    assert_eq!(identity("Ana Lopez <Ana.Lopez@Example.com>"), "ana.lopez@example.com");
+    assert_eq!(identity("  Bo  "), "bo");
+    assert_eq!(identity("Bo <>"), "bo <>");
+
+    let acts = vec![
+        paired(60, &["Ana <ana@example.com>", "Bo <bo@example.com>"]),
+        paired(30, &["Ana Lopez <ANA@example.com>"]),
+        // Listed twice under different names, counted once
+        paired(20, &["Bo <bo@example.com>", "Bob <BO@example.com>"]),
+        paired(10, &[]),
+    ];
+    assert_eq!(time_by_person(&acts), vec![
+        ("Ana <ana@example.com>".to_string(), 90),
+        ("Bo <bo@example.com>".to_string(), 80),
+    ]);
+}
+
+#[test]
+fn involves_matches_part_of_a_name_or_email() {
+    let act = paired(60, &["Ana Lopez <ana@example.com>", "Bo <bo@example.com>"]);
+    assert!(act.involves("lopez"));
+    assert!(act.involves("BO@EXAMPLE"));
+    assert!(!act.involves("carla"));
+    assert!(!paired(60, &[]).involves("ana"));
+}
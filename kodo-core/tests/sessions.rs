@@ -0,0 +1,37 @@
+use chrono::{Duration, Local, TimeZone};
+use kodo_core::sessions::{group_sessions, start_first_at};
+
+#[test]
+fn events_split_on_gaps_longer_than_timeout() {
+    let at = |h, m| Local.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap();
+    let events = vec![
+        (at(9, 40), at(9, 45), "c"),
+        (at(9, 0), at(9, 10), "a"),
+        (at(9, 20), at(9, 30), "b"),
+        (at(11, 0), at(11, 0), "d"),
+    ];
+
+    let sessions = group_sessions(events, Duration::minutes(15));
+
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].items, vec!["a", "b", "c"]);
+    assert_eq!(sessions[0].minutes(), 45);
+    assert_eq!(sessions[1].items, vec!["d"]);
+    assert_eq!(sessions[1].minutes(), 1);
+}
+
+#[test]
+fn first_session_can_start_at_an_earlier_time_that_day() {
+    let at = |d, h, m| Local.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap();
+    let events = vec![(at(2, 12, 0), at(2, 12, 0), "a"), (at(2, 15, 0), at(2, 15, 0), "b")];
+
+    let mut sessions = group_sessions(events.clone(), Duration::minutes(30));
+    start_first_at(&mut sessions, at(2, 9, 0));
+    assert_eq!(sessions[0].minutes(), 180);
+    assert_eq!(sessions[1].minutes(), 1);
+
+    // A commit from the day before says nothing about when today's work began
+    let mut sessions = group_sessions(events, Duration::minutes(30));
+    start_first_at(&mut sessions, at(1, 17, 0));
+    assert_eq!(sessions[0].minutes(), 1);
+}
@@ -0,0 +1,67 @@
+use kodo_core::standup::{previous_working_day, Standup};
+use kodo_core::{Calendar, Source};
+
+mod common;
+use common::{act, day, Build};
+
+#[test]
+fn previous_working_day_skips_weekends() {
+    assert_eq!(previous_working_day(day("2026-10-19")), day("2026-10-16")); // Monday -> Friday
+    assert_eq!(previous_working_day(day("2026-10-18")), day("2026-10-16")); // Sunday -> Friday
+    assert_eq!(previous_working_day(day("2026-10-15")), day("2026-10-14"));
+}
+
+#[test]
+fn groups_by_project_and_renders_template() {
+    let mut commit = act("Fix login redirect\n\nLonger body", 20, "2026-10-16").with_project("acme");
+    commit.source = Some(Source::git("/repo", "abc123", "Ana"));
+    let acts = vec![
+        act("Code review", 30, "2026-10-16").with_project("acme"),
+        commit,
+        act("Code review", 15, "2026-10-16").with_project("acme"),
+        act("Planning", 60, "2026-10-16"),
+        act("Something else", 45, "2026-10-15").with_project("acme"),
+    ];
+
+    let standup = Standup::build(&acts, day("2026-10-16"), &Calendar::default());
+    assert_eq!(standup.total_minutes(), 125);
+    assert_eq!(standup.groups[0].project, "acme");
+    assert_eq!(standup.groups[0].items.len(), 2);
+    assert_eq!(standup.groups[0].items[0].minutes, 45);
+
+    assert_eq!(
+        standup.render("{weekday} {date} ({total})\n{projects}"),
+        "Friday 2026-10-16 (2:05)\n\
+         *acme* (1:05)\n- Code review (0:45)\n- Fix login redirect (commit, 0:20)\n\n\
+         *(no project)* (1:00)\n- Planning (1:00)",
+    );
+    assert_eq!(standup.render("{items}"), "- Code review\n- Fix login redirect\n- Planning");
+}
+
+#[test]
+fn worktree_drafts_are_not_commits() {
+    let mut draft = act("Uncommitted work on main: lib.rs", 40, "2026-10-16").with_project("acme");
+    draft.source = Some(Source::worktree("/repo"));
+
+    let standup = Standup::build(&[draft], day("2026-10-16"), &Calendar::default());
+    assert!(!standup.groups[0].items[0].from_git);
+    assert_eq!(standup.projects_markdown(), "*acme* (0:40)\n- Uncommitted work on main: lib.rs (0:40)");
+}
+
+#[test]
+fn placeholders_in_names_are_left_alone() {
+    let acts = vec![
+        act("Document {projects} and {date}", 30, "2026-10-16").with_project("{total}"),
+        act("Match { and }", 15, "2026-10-16").with_project("{total}"),
+    ];
+    let standup = Standup::build(&acts, day("2026-10-16"), &Calendar::default());
+
+    assert_eq!(
+        standup.render("{date}: {items}"),
+        "2026-10-16: - Document {projects} and {date}\n- Match { and }",
+    );
+    assert_eq!(
+        standup.render("{total} {unknown} {\n{projects}"),
+        "0:45 {unknown} {\n*{total}* (0:45)\n- Document {projects} and {date} (0:30)\n- Match { and } (0:15)",
+    );
+}
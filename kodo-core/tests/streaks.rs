@@ -0,0 +1,28 @@
+use kodo_core::streaks::StreakStats;
+use kodo_core::{Activity, Calendar};
+
+mod common;
+use common::{act, day};
+
+fn on(date: &str) -> Activity {
+    act("work", 30, date)
+}
+
+#[test]
+fn current_and_longest_streaks() {
+    let acts = vec![
+        on("2026-10-05"), on("2026-10-06"), on("2026-10-07"), on("2026-10-08"),
+        on("2026-10-10"),
+        on("2026-10-16"), on("2026-10-17"),
+    ];
+
+    let stats = StreakStats::compute(&acts, &Calendar::default(), day("2026-10-18"));
+
+    // Nothing logged today yet, so the run ending yesterday is still current.
+    assert_eq!(stats.current, 2);
+    assert_eq!(stats.longest, 4);
+    assert_eq!(stats.longest_end, Some(day("2026-10-08")));
+    assert_eq!(stats.active_days, 7);
+    assert_eq!(stats.missed_days.len(), 6);
+    assert_eq!(stats.weekly, vec![(day("2026-10-05"), 5), (day("2026-10-12"), 2)]);
+}
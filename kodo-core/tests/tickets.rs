@@ -0,0 +1,68 @@
+use kodo_core::report::time_by_ticket;
+use kodo_core::{Activity, Config};
+
+mod common;
+use common::{act, Build};
+
+#[test]
+fn default_patterns_find_issue_and_jira_refs() {
+    let matcher = Config::default().ticket_matcher().unwrap();
+
+    let found = matcher.extract("Fix login redirect (#123)\n\nRelates to JIRA-456\nFixes: ABC-7");
+    assert_eq!(found, vec!["#123", "JIRA-456", "ABC-7"]);
+}
+
+#[test]
+fn default_patterns_skip_standards() {
+    let matcher = Config::default().ticket_matcher().unwrap();
+
+    let found = matcher.extract("PAY-12: store dates as ISO-8601 in UTF-8, hash with SHA-256 (RFC-3339, CVE-2024)");
+    assert_eq!(found, vec!["PAY-12"]);
+    assert_eq!(matcher.extract("ISOX-3 and SHA-1a"), vec!["ISOX-3"]);
+}
+
+#[test]
+fn excluded_prefixes_are_configurable() {
+    // A team whose Jira project is called MD
+    let config = Config { ticket_exclude: vec!["UTF".to_string()], ..Default::default() };
+    let matcher = config.ticket_matcher().unwrap();
+    assert_eq!(matcher.extract("MD-12: write UTF-8"), vec!["MD-12"]);
+
+    let config = Config { ticket_exclude: Vec::new(), ..Default::default() };
+    assert_eq!(config.ticket_matcher().unwrap().extract("UTF-8"), vec!["UTF-8"]);
+
+    let config: Config = serde_json::from_str(r#"{"ticket_patterns": ["[A-Z]+-\\d+"]}"#).unwrap();
+    assert_eq!(config.ticket_matcher().unwrap().extract("SHA-256 and MD-5"), Vec::<String>::new());
+}
+
+#[test]
+fn custom_pattern_uses_first_capture_group() {
+    let config = Config {
+        ticket_patterns: vec![r"ticket/(\d+)".to_string()],
+        ..Default::default()
+    };
+    let matcher = config.ticket_matcher().unwrap();
+
+    assert_eq!(matcher.extract("see ticket/42 and ticket/42 again"), vec!["42"]);
+    assert!(matcher.extract("JIRA-456").is_empty());
+}
+
+#[test]
+fn renaming_rereads_tickets() {
+    let matcher = Config::default().ticket_matcher().unwrap();
+    let mut act = Activity { name: "Fix PAY-1".to_string(), tickets: vec!["PAY-1".to_string()], ..Default::default() };
+
+    act.rename("Fix PAY-2", &matcher);
+    assert_eq!(act.tickets, vec!["PAY-2"]);
+    act.rename("Tidy up", &matcher);
+    assert!(act.tickets.is_empty());
+}
+
+#[test]
+fn ticket_totals_count_each_linked_ticket() {
+    let a = act("PAY-1 and PAY-2", 30, "2026-10-12").with_tickets(&["PAY-1", "PAY-2"]);
+    let b = act("PAY-2", 20, "2026-10-12").with_tickets(&["PAY-2"]);
+
+    let totals = time_by_ticket(&[a, b]);
+    assert_eq!(totals, vec![("PAY-2".to_string(), 50), ("PAY-1".to_string(), 30)]);
+}
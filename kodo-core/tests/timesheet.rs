@@ -0,0 +1,38 @@
+use kodo_core::timesheet::{Rounding, RoundingMode, Timesheet};
+use kodo_core::Config;
+
+mod common;
+use common::{act, day, Build};
+
+#[test]
+fn rounding_modes() {
+    let up = Rounding { increment: 15, mode: RoundingMode::Up };
+    let nearest = Rounding { increment: 15, mode: RoundingMode::Nearest };
+    let down = Rounding { increment: 15, mode: RoundingMode::Down };
+
+    assert_eq!((up.apply(1), up.apply(15), up.apply(16)), (15, 15, 30));
+    assert_eq!((nearest.apply(7), nearest.apply(8)), (0, 15));
+    assert_eq!(down.apply(29), 15);
+    assert_eq!(Rounding::default().apply(7), 7);
+}
+
+#[test]
+fn lines_group_rounded_activities_by_day_and_project() {
+    let acts = vec![
+        act("Fix login", 52, "2026-10-12").with_project("acme"),
+        act("Review", 7, "2026-10-12").with_project("acme"),
+        act("Internal", 30, "2026-10-12"),
+        act("Outside range", 30, "2026-09-30").with_project("acme"),
+    ];
+    let config = Config {
+        rounding: Rounding { increment: 15, mode: RoundingMode::Up },
+        ..Default::default()
+    };
+
+    let sheet = Timesheet::build(&acts, day("2026-10-01"), day("2026-10-31"), Some("acme"), &config).unwrap();
+
+    assert_eq!(sheet.lines.len(), 1);
+    assert_eq!(sheet.lines[0].minutes, 75);
+    assert_eq!(sheet.lines[0].descriptions, vec!["Fix login", "Review"]);
+    assert!(sheet.to_csv().unwrap().ends_with("total,,75,1.25,75,\n"));
+}
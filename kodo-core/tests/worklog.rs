@@ -0,0 +1,102 @@
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use kodo_core::worklog::{worklogs, Worklog, WorklogQueue, WorklogSink};
+use std::cell::RefCell;
+
+mod common;
+use common::{act, day, utc, Build};
+
+/// Accepts every worklog except those for `NOPE-*` issues, the way Jira
+/// rejects unknown issues, and remembers where each was sent.
+#[derive(Default)]
+struct FakeJira {
+    sent: RefCell<Vec<String>>,
+}
+
+impl WorklogSink for FakeJira {
+    fn add_worklog(&self, issue: &str, _worklog: &Worklog) -> Result<Option<String>> {
+        if issue.starts_with("NOPE-") {
+            bail!("Jira answered 404: Issue does not exist");
+        }
+        let mut sent = self.sent.borrow_mut();
+        sent.push(issue.to_string());
+        Ok(Some(format!("{}", 10000 + sent.len() - 1)))
+    }
+}
+
+#[test]
+fn builds_worklogs_for_jira_issues_only() {
+    let mut timed = act("Fix the parser", 90, "2026-10-15").with_id(1).with_tickets(&["ABC-12"]);
+    timed.started_at = Some(DateTime::parse_from_rfc3339("2026-10-15T13:00:00+02:00").unwrap());
+    let acts = vec![
+        timed,
+        act("Pairing on ABC-12 and OPS-3", 45, "2026-10-15").with_id(2).with_tickets(&["ABC-12", "OPS-3"]),
+        act("GitHub issue", 30, "2026-10-15").with_id(3).with_tickets(&["#42"]),
+        act("Outside the range", 30, "2026-10-01").with_id(4).with_tickets(&["ABC-12"]),
+    ];
+
+    let uploads = worklogs(&acts, day("2026-10-12"), day("2026-10-18"), None, &utc());
+    let summary: Vec<(u32, &str, &str, u32)> = uploads
+        .iter()
+        .map(|u| (u.activity_id, u.issue.as_str(), u.worklog.started.as_str(), u.worklog.time_spent_seconds))
+        .collect();
+    assert_eq!(summary, vec![
+        (2, "ABC-12", "2026-10-15T09:00:00.000+0000", 23 * 60),
+        (2, "OPS-3", "2026-10-15T09:00:00.000+0000", 22 * 60),
+        (1, "ABC-12", "2026-10-15T13:00:00.000+0200", 90 * 60),
+    ]);
+
+    let json = serde_json::to_value(&uploads[2].worklog).unwrap();
+    assert_eq!(json, serde_json::json!({
+        "started": "2026-10-15T13:00:00.000+0200",
+        "timeSpentSeconds": 5400,
+        "comment": "Fix the parser",
+    }));
+}
+
+#[test]
+fn submits_pending_worklogs_and_saves_after_each() {
+    let acts = vec![
+        act("Fix the parser", 60, "2026-10-15").with_id(1).with_tickets(&["ABC-12"]),
+        act("Wrong key", 30, "2026-10-15").with_id(2).with_tickets(&["NOPE-1"]),
+        act("Review", 30, "2026-10-15").with_id(3).with_tickets(&["ABC-13"]),
+    ];
+    let mut queue = WorklogQueue::default();
+    assert_eq!(queue.add(worklogs(&acts, day("2026-10-15"), day("2026-10-15"), None, &utc())), 3);
+
+    let jira = FakeJira::default();
+    let mut saves = Vec::new();
+    let summary = queue.submit(&jira, |q| {
+        saves.push((q.pending.len(), q.uploaded.len()));
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(*jira.sent.borrow(), vec!["ABC-12", "ABC-13"]);
+    // Each accepted worklog leaves the pending list before the next request
+    assert_eq!(saves, vec![(2, 1), (1, 2)]);
+    assert_eq!(summary.uploaded, 2);
+    assert_eq!(summary.failed, vec![("NOPE-1".to_string(), "Jira answered 404: Issue does not exist".to_string())]);
+    assert_eq!(queue.uploaded[0].worklog_id.as_deref(), Some("10000"));
+    assert_eq!(queue.pending.len(), 1);
+    assert_eq!(queue.pending[0].issue, "NOPE-1");
+    assert!(queue.pending[0].last_error.is_some());
+
+    // Exporting the same range again only keeps what is already pending
+    assert_eq!(queue.add(worklogs(&acts, day("2026-10-15"), day("2026-10-15"), None, &utc())), 0);
+}
+
+#[test]
+fn stops_when_saving_fails() {
+    let acts = vec![
+        act("Fix the parser", 60, "2026-10-15").with_id(1).with_tickets(&["ABC-12"]),
+        act("Review", 30, "2026-10-15").with_id(2).with_tickets(&["ABC-13"]),
+    ];
+    let mut queue = WorklogQueue::default();
+    queue.add(worklogs(&acts, day("2026-10-15"), day("2026-10-15"), None, &utc()));
+
+    let jira = FakeJira::default();
+    let err = queue.submit(&jira, |_| bail!("disk full")).unwrap_err();
+    assert_eq!(err.to_string(), "disk full");
+    assert_eq!(*jira.sent.borrow(), vec!["ABC-12"]);
+    assert_eq!(queue.pending.len(), 1);
+}